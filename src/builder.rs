@@ -9,6 +9,7 @@ use std::{
     fs::File,
     io::Write,
     path::{Path, PathBuf},
+    time::SystemTime,
 };
 
 use anc_assembler::assembler::assemble_module_node;
@@ -34,7 +35,7 @@ use anc_parser_asm::{parser::parse_from_str, NAME_PATH_SEPARATOR};
 use resolve_path::PathResolveExt;
 
 use crate::{
-    entry::{FileMeta, ModuleConfig, RuntimeProperty},
+    entry::{FileMeta, ModuleConfig, RuntimeLimitConfig, RuntimeProperty, TestConfig},
     fetcher::{
         checkout_module, fetch_module, get_shared_module_remote_location,
         RemoteRepositoryResourceLocation,
@@ -45,15 +46,26 @@ use crate::{
         get_mata_file_path, get_mata_file_path_by_full_name, get_module_config_file_path,
         get_module_folder_app_path, get_module_folder_output_path, get_module_folder_src_path,
         get_module_folder_tests_path, get_object_file_path, get_output_folder_hash_path,
+        get_shared_module_image_file_path_by_dynamic_link_module_entry,
         get_shared_module_image_file_path_by_hash_path,
+        get_single_file_application_cache_file_path,
     },
     peekableiter::PeekableIter,
     source_scanner::{get_file_timestamp, list_assembly_files, PathAndTimestamp},
-    RuntimeError, DIRECTORY_NAME_VERSION_REMOTE, FILE_NAME_MODULE_CONFIG,
+    RuntimeError, BUILTIN_MODULE_NAME_STD, DIRECTORY_NAME_APP, DIRECTORY_NAME_VERSION_REMOTE,
+    FILE_EXTENSION_IMAGE, FILE_NAME_MODULE_CONFIG,
 };
 
 pub const INLINE_CONFIG_MARK: &str = "@config";
 
+/// The function `_start_{unit_name}` of a single-file application
+/// is the additional executable unit `{unit_name}`.
+pub const EXECUTABLE_UNIT_FUNCTION_NAME_PREFIX: &str = "_start_";
+
+/// The functions `bench_*` of a benchmark (i.e. a single-file application in
+/// the folder `{module_folder}/benches`) are the benchmarks.
+pub const BENCHMARK_FUNCTION_NAME_PREFIX: &str = "bench_";
+
 /// Compile the specified module and generate the module image file.
 /// The last modification time of source files is checked and no
 /// module image is generated if all source files remain unchanged.
@@ -672,6 +684,330 @@ pub fn build_application_by_single_file(
     script_file_path: &Path,
     runtime_property: &RuntimeProperty,
     logger: &mut dyn Write,
+) -> Result<(ImageCommonEntry, ImageIndexEntry, Vec<u8>), RuntimeError> {
+    build_application_by_single_file_with_dependencies(
+        script_file_path,
+        HashMap::new(),
+        false,
+        runtime_property,
+        logger,
+    )
+}
+
+/// The version of the cached images of single-file applications, it is a part of
+/// the cache key, and has to be increased when the way of building single-file
+/// applications changes, e.g. the generated submodules of the executable units.
+const SINGLE_FILE_APPLICATION_CACHE_FORMAT_VERSION: u32 = 1;
+
+/// The maximum number of the cached images of single-file applications, the
+/// least recently used ones are removed when a new image is saved.
+const SINGLE_FILE_APPLICATION_CACHE_CAPACITY: usize = 256;
+
+/// Load the image of a single-file application from the cache, or build it
+/// in memory and save it to the cache.
+///
+/// The cached images are located in the folder `{runtime_home}/cache`, they are
+/// named by the hash of the source code (which includes the inline configuration),
+/// the folder of the application file (the paths of local dependencies are
+/// relative to it), whether the builtin module `std` is installed, and the
+/// identity of the runtime, i.e. the cache format version, the version and the
+/// executable file of the runtime (so the images are rebuilt when the runtime is
+/// rebuilt with another builder, assembler or linker).
+///
+/// A cached image is reused only if none of the dependent modules is rebuilt,
+/// and none of the dependent image files is modified at or after the time of the
+/// cached image. The cached images are evicted by the least recently used order,
+/// see `SINGLE_FILE_APPLICATION_CACHE_CAPACITY`.
+pub fn load_or_build_application_by_single_file(
+    script_file_path: &Path,
+    runtime_property: &RuntimeProperty,
+    logger: &mut dyn Write,
+) -> Result<(ImageCommonEntry, ImageIndexEntry, Vec<u8>), RuntimeError> {
+    let source_code = std::fs::read_to_string(script_file_path)
+        .map_err(|e| RuntimeError::Message(format!("{}", e)))?;
+
+    let hash_string =
+        get_single_file_application_hash_string(script_file_path, &source_code, runtime_property);
+    let cache_directory = runtime_property.get_cache_directory();
+    let cache_file_path =
+        get_single_file_application_cache_file_path(&cache_directory, &hash_string);
+
+    if cache_file_path.exists() {
+        let image_binary =
+            std::fs::read(&cache_file_path).map_err(|e| RuntimeError::Message(format!("{}", e)))?;
+
+        // a broken cache file is simply replaced.
+        if let Ok((common_entry, index_entry)) = read_image_file(&image_binary) {
+            if is_cached_application_up_to_date(
+                script_file_path,
+                &cache_file_path,
+                &common_entry,
+                &index_entry,
+                runtime_property,
+                logger,
+            )? {
+                // mark the cached image as recently used, it is safe because
+                // the dependent image files are not newer than now.
+                let _ = File::options()
+                    .write(true)
+                    .open(&cache_file_path)
+                    .and_then(|file| file.set_modified(SystemTime::now()));
+
+                return Ok((common_entry, index_entry, image_binary));
+            }
+        }
+    }
+
+    let (common_entry, index_entry, image_binary) =
+        build_application_by_single_file(script_file_path, runtime_property, logger)?;
+
+    // the cache is only an optimization, so a failure of saving
+    // (e.g. the `{runtime_home}` is read-only) is ignored.
+    if std::fs::create_dir_all(&cache_directory).is_ok()
+        && std::fs::write(&cache_file_path, &image_binary).is_ok()
+    {
+        let _ = evict_single_file_application_cache(
+            &cache_directory,
+            SINGLE_FILE_APPLICATION_CACHE_CAPACITY,
+        );
+    }
+
+    Ok((common_entry, index_entry, image_binary))
+}
+
+/// Remove the least recently used (i.e. the earliest modified, see
+/// `load_or_build_application_by_single_file`) cached images
+/// until there are at most `capacity` images.
+fn evict_single_file_application_cache(
+    cache_directory: &Path,
+    capacity: usize,
+) -> std::io::Result<()> {
+    let mut cache_files = vec![];
+
+    for entry in std::fs::read_dir(cache_directory)? {
+        let file_path = entry?.path();
+        if file_path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            != Some(FILE_EXTENSION_IMAGE)
+        {
+            continue;
+        }
+
+        let modified = file_path.metadata()?.modified()?;
+        cache_files.push((modified, file_path));
+    }
+
+    if cache_files.len() <= capacity {
+        return Ok(());
+    }
+
+    // the most recently used first
+    cache_files.sort_by(|left, right| right.0.cmp(&left.0));
+
+    for (_, file_path) in &cache_files[capacity..] {
+        std::fs::remove_file(file_path)?;
+    }
+
+    Ok(())
+}
+
+fn get_single_file_application_hash_string(
+    script_file_path: &Path,
+    source_code: &str,
+    runtime_property: &RuntimeProperty,
+) -> String {
+    let script_folder_path = script_file_path.parent().unwrap();
+    let script_folder_path_buf = script_folder_path
+        .canonicalize()
+        .unwrap_or(script_folder_path.to_path_buf());
+
+    let mut hasher = StableHasher::new();
+    hasher.write_part(&SINGLE_FILE_APPLICATION_CACHE_FORMAT_VERSION.to_le_bytes());
+    hasher.write_part(env!("CARGO_PKG_VERSION").as_bytes());
+
+    // the builder, assembler and linker are compiled into the runtime executable file,
+    // so the cached images are invalidated when the runtime is rebuilt.
+    let (exec_file_size, exec_file_modified_nanos) = std::env::current_exe()
+        .and_then(|exec_file_path| exec_file_path.metadata())
+        .map(|metadata| {
+            let modified_nanos = metadata
+                .modified()
+                .ok()
+                .and_then(|modified| modified.duration_since(SystemTime::UNIX_EPOCH).ok())
+                .map(|duration| duration.as_nanos())
+                .unwrap_or(0);
+            (metadata.len(), modified_nanos)
+        })
+        .unwrap_or((0, 0));
+    hasher.write_part(&exec_file_size.to_le_bytes());
+    hasher.write_part(&exec_file_modified_nanos.to_le_bytes());
+
+    hasher.write_part(script_folder_path_buf.to_string_lossy().as_bytes());
+
+    // the file name is the module name when the source code has no inline configuration.
+    hasher.write_part(
+        script_file_path
+            .file_stem()
+            .unwrap_or_default()
+            .as_encoded_bytes(),
+    );
+    hasher.write_part(source_code.as_bytes());
+
+    // the builtin module `std` is a dependency only if it is installed
+    let std_installed = is_builtin_module_installed(BUILTIN_MODULE_NAME_STD, runtime_property);
+    hasher.write_part(&[std_installed as u8]);
+
+    format!("{:032x}", hasher.finish())
+}
+
+/// The 128-bit FNV-1a hash, unlike `DefaultHasher` of the standard library,
+/// its result is stable across Rust releases and platforms, so it can be
+/// used for the file names of the cache.
+struct StableHasher {
+    state: u128,
+}
+
+impl StableHasher {
+    const OFFSET_BASIS: u128 = 0x6c62272e07bb014262b821756295c58d;
+    const PRIME: u128 = 0x0000000001000000000000000000013b;
+
+    fn new() -> Self {
+        Self {
+            state: Self::OFFSET_BASIS,
+        }
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.state ^= *byte as u128;
+            self.state = self.state.wrapping_mul(Self::PRIME);
+        }
+    }
+
+    /// Write a part with its length, so that the boundaries of
+    /// the parts are kept, e.g. ("ab", "c") differs from ("a", "bc").
+    fn write_part(&mut self, bytes: &[u8]) {
+        self.write(&(bytes.len() as u64).to_le_bytes());
+        self.write(bytes);
+    }
+
+    fn finish(&self) -> u128 {
+        self.state
+    }
+}
+
+fn is_cached_application_up_to_date(
+    script_file_path: &Path,
+    cache_file_path: &Path,
+    common_entry: &ImageCommonEntry,
+    index_entry: &ImageIndexEntry,
+    runtime_property: &RuntimeProperty,
+    logger: &mut dyn Write,
+) -> Result<bool, RuntimeError> {
+    // the dependent modules have to be built (if they are changed)
+    // even if the application is loaded from the cache.
+    let module_path = script_file_path.parent().unwrap();
+    let (_, _, any_module_changed) = build_all_dependent_modules_by_dependency_tree(
+        &common_entry.name,
+        module_path,
+        common_entry,
+        ModuleDependencyType::Local,
+        runtime_property,
+        logger,
+    )?;
+
+    if any_module_changed {
+        return Ok(false);
+    }
+
+    // the modification time (instead of the timestamp in seconds, see
+    // `get_file_timestamp`) is compared, and the image that is modified at
+    // the same time is also treated as newer, because the precision of the
+    // modification time depends on the file system.
+    let get_modified = |file_path: &Path| {
+        file_path
+            .metadata()
+            .and_then(|metadata| metadata.modified())
+            .map_err(|e| RuntimeError::Message(format!("{}", e)))
+    };
+
+    let cache_modified = get_modified(cache_file_path)?;
+
+    for dynamic_link_module_entry in &index_entry.dynamic_link_module_entries[1..] {
+        let image_file_path = get_shared_module_image_file_path_by_dynamic_link_module_entry(
+            dynamic_link_module_entry,
+            runtime_property,
+        );
+
+        if !image_file_path.exists() || get_modified(&image_file_path)? >= cache_modified {
+            return Ok(false);
+        }
+    }
+
+    Ok(true)
+}
+
+/// Build a single-file application located in a module folder in memory,
+/// e.g. an integration test in the folder `{module_folder}/integration`, or
+/// a benchmark in the folder `{module_folder}/benches`.
+///
+/// The module is added to the dependencies of the application as a local module,
+/// so the application uses the module the same way as an external consumer,
+/// it can only access the public functions and data of the module.
+///
+/// If `include_benchmarks` is `true`, each function `bench_*` of the application
+/// becomes an entry point with the same name, see `BENCHMARK_FUNCTION_NAME_PREFIX`.
+pub fn build_application_by_single_file_in_module(
+    script_file_path: &Path,
+    module_path: &Path,
+    module_name: &str,
+    include_benchmarks: bool,
+    runtime_property: &RuntimeProperty,
+    logger: &mut dyn Write,
+) -> Result<(ImageCommonEntry, ImageIndexEntry, Vec<u8>), RuntimeError> {
+    // the path of local module dependency is relative to the folder of the
+    // application file, e.g. "integration/foo.anca" -> "..", and
+    // "integration/network/http.anca" -> "../.."
+    let script_folder_path = script_file_path.parent().unwrap();
+    let depth = script_folder_path
+        .strip_prefix(module_path)
+        .map_err(|_| {
+            RuntimeError::Message(format!(
+                "File \"{}\" is not located in the module \"{}\".",
+                script_file_path.display(),
+                module_name
+            ))
+        })?
+        .components()
+        .count();
+    let relative_path = vec![".."; depth].join("/");
+
+    let module_dependency_text = format!("module::local({{path: \"{}\"}})", relative_path);
+    let module_dependency: ModuleDependency = ason::from_str(&module_dependency_text)
+        .map_err(|e| RuntimeError::Message(e.with_source(&module_dependency_text)))?;
+
+    let mut dependencies = HashMap::new();
+    dependencies.insert(module_name.to_owned(), module_dependency);
+
+    build_application_by_single_file_with_dependencies(
+        script_file_path,
+        dependencies,
+        include_benchmarks,
+        runtime_property,
+        logger,
+    )
+}
+
+/// The `dependencies` are added to the module configuration of the
+/// single-file application, unless the modules with the same names
+/// have been declared in the inline configuration.
+fn build_application_by_single_file_with_dependencies(
+    script_file_path: &Path,
+    dependencies: HashMap<String, ModuleDependency>,
+    include_benchmarks: bool,
+    runtime_property: &RuntimeProperty,
+    logger: &mut dyn Write,
 ) -> Result<(ImageCommonEntry, ImageIndexEntry, Vec<u8>), RuntimeError> {
     // todo: check extension name
     // RuntimeError::Message(
@@ -680,39 +1016,182 @@ pub fn build_application_by_single_file(
     let source_code = std::fs::read_to_string(script_file_path)
         .map_err(|e| RuntimeError::Message(format!("{}", e)))?;
 
+    let module_path = script_file_path.parent().unwrap();
+    let file_base_name = script_file_path.file_stem().unwrap().to_str().unwrap();
+
+    build_application_by_source_code_with_dependencies(
+        &source_code,
+        module_path,
+        file_base_name,
+        dependencies,
+        include_benchmarks,
+        runtime_property,
+        logger,
+    )
+}
+
+/// Build an application from the source code in memory, e.g. the
+/// input of the REPL. The source code is handled the same as a
+/// single-file application, it can contain an inline configuration.
+///
+/// - `module_path`: the folder that the paths of local dependencies are relative to.
+/// - `default_module_name`: the module name if there is no inline configuration.
+pub fn build_application_by_source_code(
+    source_code: &str,
+    module_path: &Path,
+    default_module_name: &str,
+    runtime_property: &RuntimeProperty,
+    logger: &mut dyn Write,
+) -> Result<(ImageCommonEntry, ImageIndexEntry, Vec<u8>), RuntimeError> {
+    build_application_by_source_code_with_dependencies(
+        source_code,
+        module_path,
+        default_module_name,
+        HashMap::new(),
+        false,
+        runtime_property,
+        logger,
+    )
+}
+
+fn build_application_by_source_code_with_dependencies(
+    source_code_with_shebang: &str,
+    module_path: &Path,
+    default_module_name: &str,
+    dependencies: HashMap<String, ModuleDependency>,
+    include_benchmarks: bool,
+    runtime_property: &RuntimeProperty,
+    logger: &mut dyn Write,
+) -> Result<(ImageCommonEntry, ImageIndexEntry, Vec<u8>), RuntimeError> {
+    let source_code = remove_shebang_line(source_code_with_shebang);
+
     let module_config_from_file_opt =
-        load_inline_config_from_single_file_application_source(&source_code)?;
+        load_inline_config_from_single_file_application_source(source_code)?;
+
+    let mut module_config = if let Some(module_config_from_file) = module_config_from_file_opt {
+        // a single-file application is always built from the source code,
+        // so it can not be sealed.
+        if module_config_from_file.seal {
+            return Err(RuntimeError::Message(format!(
+                "The single-file application \"{}\" can not be sealed, remove the \"seal\" flag from the inline configuration.",
+                module_config_from_file.name
+            )));
+        }
 
-    let module_config = if let Some(module_config_from_file) = module_config_from_file_opt {
-        module_config_from_file
+        // todo:: the remote and share modules, they have to be fetched from the
+        // repository and resolved by the registry, which are not available yet.
+        let mut unsupported_module_names = module_config_from_file
+            .modules
+            .iter()
+            .filter(|(_, module_dependency)| {
+                matches!(
+                    module_dependency,
+                    ModuleDependency::Remote(_) | ModuleDependency::Share(_)
+                )
+            })
+            .map(|(name, _)| name.as_str())
+            .collect::<Vec<_>>();
+
+        if !unsupported_module_names.is_empty() {
+            unsupported_module_names.sort();
+            return Err(RuntimeError::Message(format!(
+                "The single-file application \"{}\" depends on the remote or share module(s) \"{}\", which are not supported yet, use the local or runtime modules instead.",
+                module_config_from_file.name,
+                unsupported_module_names.join("\", \"")
+            )));
+        }
 
-        // todo:: remove `seal` flag if it exists
+        module_config_from_file
     } else {
-        let file_base_name = script_file_path.file_stem().unwrap().to_str().unwrap();
-
         ModuleConfig {
-            name: file_base_name.to_owned(),
+            name: default_module_name.to_owned(),
             version: "1.0.0".to_owned(),
             edition: RUNTIME_EDITION_STRING.to_owned(),
             properties: HashMap::new(),
-            modules: HashMap::new(), // todo:: add std module
+            modules: HashMap::new(),
             libraries: HashMap::new(),
             seal: false,
+            test: TestConfig::default(),
+            runtime: RuntimeLimitConfig::default(),
         }
     };
 
+    for (name, module_dependency) in dependencies {
+        module_config
+            .modules
+            .entry(name)
+            .or_insert(module_dependency);
+    }
+
+    // the builtin module `std` is added implicitly if it is installed
+    // and has not been declared in the inline configuration.
+    if is_builtin_module_installed(BUILTIN_MODULE_NAME_STD, runtime_property) {
+        module_config
+            .modules
+            .entry(BUILTIN_MODULE_NAME_STD.to_owned())
+            .or_insert(ModuleDependency::Runtime);
+    }
+
     let (import_module_entries, external_library_entries) =
         module_config.get_dependencies_by_module_config();
 
-    let module_path = script_file_path.parent().unwrap();
     let module_name = module_config.name.clone();
-    let main_module = assemble(
+    let main_submodule = assemble(
         &import_module_entries,
         &external_library_entries,
         &module_name,
-        &source_code,
+        source_code,
     )?;
 
+    // generate a submodule `{module_name}::app::{unit_name}` for each additional
+    // executable unit, the same as the files in the folder `app` of a module,
+    // its function `_start` calls the function `_start_{unit_name}`.
+    //
+    // the benchmarks are generated the same way, the unit name
+    // of a benchmark is the name of the function `bench_*`.
+    //
+    // `Vec<(unit_name, unit_function_name)>`
+    let mut units = list_executable_unit_names_from_single_file_application_source(source_code)
+        .into_iter()
+        .map(|unit_name| {
+            let unit_function_name =
+                format!("{}{}", EXECUTABLE_UNIT_FUNCTION_NAME_PREFIX, unit_name);
+            (unit_name, unit_function_name)
+        })
+        .collect::<Vec<_>>();
+
+    if include_benchmarks {
+        units.extend(
+            list_benchmark_function_names_from_single_file_application_source(source_code)
+                .into_iter()
+                .map(|function_name| (function_name.clone(), function_name)),
+        );
+    }
+
+    let main_module = if units.is_empty() {
+        main_submodule
+    } else {
+        let mut submodule_entries = vec![main_submodule];
+
+        for (unit_name, unit_function_name) in &units {
+            let unit_source_code = format!(
+                "import fn module::{}()->i32\n\nfn _start() ->i32 {{\n    call({})\n}}\n",
+                unit_function_name, unit_function_name
+            );
+
+            let unit_submodule = assemble(
+                &import_module_entries,
+                &external_library_entries,
+                &format!("{}::{}::{}", module_name, DIRECTORY_NAME_APP, unit_name),
+                &unit_source_code,
+            )?;
+            submodule_entries.push(unit_submodule);
+        }
+
+        let module_version = EffectiveVersion::from_str(&module_config.version);
+        link(&module_name, &module_version, &submodule_entries)?
+    };
+
     // build all dependent modules
     let (mut image_common_entries, mut dynamic_link_module_entries, _) =
         build_all_dependent_modules_by_dependency_tree(
@@ -747,6 +1226,13 @@ pub fn build_application_by_single_file(
     Ok((common_entry, index_entry, buffer))
 }
 
+fn is_builtin_module_installed(module_name: &str, runtime_property: &RuntimeProperty) -> bool {
+    let module_path = runtime_property
+        .get_builtin_modules_directory()
+        .join(module_name);
+    get_module_config_file_path(&module_path).exists()
+}
+
 /// If a module is referenced multiple times in the dependency tree with
 /// different parameters, there is a risk that the program may not run correctly
 /// because the application will only select one of the dependent parameters.
@@ -1256,19 +1742,187 @@ fn get_module_dependency_type(module_dependency: &ModuleDependency) -> ModuleDep
     }
 }
 
+/// Remove the shebang line (e.g. `#!/usr/bin/env -S ancrt run`) of a single-file
+/// application, so that the application can be run directly as an executable file.
+///
+/// The shebang line is not a comment of the source code, so the scanners of the
+/// inline configuration and the executable units would misread the comment marks
+/// in it, e.g. a `/*` in the shebang line hides the inline configuration.
+///
+/// The line break of the shebang line is kept, so the line numbers in the
+/// error messages of the parser remain correct.
+pub fn remove_shebang_line(source_code: &str) -> &str {
+    if !source_code.starts_with("#!") {
+        return source_code;
+    }
+
+    match source_code.find('\n') {
+        Some(pos) => &source_code[pos..],
+        None => "",
+    }
+}
+
+/// Find the additional executable units of a single-file application,
+/// i.e. the functions `pub fn _start_{unit_name}() -> i32`.
+///
+/// Returns the unit names in the order of definition.
+pub fn list_executable_unit_names_from_single_file_application_source(
+    source_code: &str,
+) -> Vec<String> {
+    list_function_names_from_single_file_application_source(
+        source_code,
+        EXECUTABLE_UNIT_FUNCTION_NAME_PREFIX,
+    )
+    .into_iter()
+    .map(|function_name| function_name[EXECUTABLE_UNIT_FUNCTION_NAME_PREFIX.len()..].to_owned())
+    .collect()
+}
+
+/// Find the benchmarks of a single-file application,
+/// i.e. the functions `pub fn bench_*() -> i32`.
+///
+/// Returns the function names in the order of definition.
+pub fn list_benchmark_function_names_from_single_file_application_source(
+    source_code: &str,
+) -> Vec<String> {
+    list_function_names_from_single_file_application_source(
+        source_code,
+        BENCHMARK_FUNCTION_NAME_PREFIX,
+    )
+}
+
+/// Find the public functions whose name starts with `prefix` (and is longer than it),
+/// i.e. `pub fn {prefix}*`, the comments and strings are skipped.
+///
+/// Returns the function names in the order of definition.
+fn list_function_names_from_single_file_application_source(
+    source_code: &str,
+    prefix: &str,
+) -> Vec<String> {
+    let source_code = remove_shebang_line(source_code);
+
+    let mut chars = source_code.chars();
+    let tokens = SourceTokenIter::new(&mut chars);
+
+    let mut function_names: Vec<String> = vec![];
+    let mut last_words = [String::new(), String::new()];
+    let mut word = String::new();
+
+    for token in tokens {
+        if let SourceToken::Char(c) = token {
+            if c.is_ascii_alphanumeric() || c == '_' {
+                word.push(c);
+                continue;
+            }
+        }
+
+        if !word.is_empty() {
+            if last_words[0] == "pub"
+                && last_words[1] == "fn"
+                && word.len() > prefix.len()
+                && word.starts_with(prefix)
+                && !function_names.contains(&word)
+            {
+                function_names.push(word.clone());
+            }
+
+            last_words.rotate_left(1);
+            last_words[1] = std::mem::take(&mut word);
+        }
+    }
+
+    function_names
+}
+
+/// Check whether all brackets of the source code are closed and the block
+/// comments are terminated, the brackets in comments and strings are ignored.
+///
+/// It is used by the REPL to decide whether the input continues on the next line.
+pub fn is_source_code_complete(source_code: &str) -> bool {
+    let mut chars = source_code.chars();
+    let tokens = SourceTokenIter::new(&mut chars);
+
+    let mut depth: isize = 0;
+
+    for token in tokens {
+        match token {
+            SourceToken::Char('(' | '[' | '{') => depth += 1,
+            SourceToken::Char(')' | ']' | '}') => depth -= 1,
+            SourceToken::BlockComment(None) => {
+                // the block comment is not terminated
+                return false;
+            }
+            _ => {
+                // consume
+            }
+        }
+    }
+
+    depth <= 0
+}
+
 pub fn load_inline_config_from_single_file_application_source(
     source_code: &str,
 ) -> Result<Option<ModuleConfig>, RuntimeError> {
+    let source_code = remove_shebang_line(source_code);
+
     // search the "/*   @config {...}   */"
     //                ^               ^
     //                |               |
     //              whitespaces (' ', '\t', '\n') are allowed
 
     let mut chars = source_code.chars();
-    let mut iter = PeekableIter::new(&mut chars, 2);
+    let tokens = SourceTokenIter::new(&mut chars);
+
+    for token in tokens {
+        if let SourceToken::BlockComment(Some(comment_text)) = token {
+            let trimmed_text = comment_text.trim();
+            if trimmed_text.starts_with(INLINE_CONFIG_MARK) {
+                let (_, config_text) = trimmed_text.split_at("config!".len());
+                let module_config = ason::from_str(config_text)
+                    .map_err(|e| RuntimeError::Message(e.with_source(config_text)))?;
+                return Ok(Some(module_config));
+            }
+        }
+    }
+    Ok(None)
+}
 
-    while let Some(prev_char) = iter.next() {
-        match prev_char {
+/// The tokens of the source code for the scanners above (e.g. finding the
+/// inline configuration and the executable units), the comments and strings
+/// are recognized so that the text in them is not taken as code.
+enum SourceToken {
+    /// A character which is not in a comment or a string.
+    Char(char),
+    LineComment,
+
+    /// The content of a string is skipped.
+    String,
+
+    /// The text of a block comment (the nested block comments are excluded),
+    /// it is `None` if the block comment is not terminated.
+    BlockComment(Option<String>),
+}
+
+struct SourceTokenIter<'a> {
+    iter: PeekableIter<'a, char>,
+}
+
+impl<'a> SourceTokenIter<'a> {
+    fn new(upstream: &'a mut dyn Iterator<Item = char>) -> Self {
+        Self {
+            iter: PeekableIter::new(upstream, 2),
+        }
+    }
+}
+
+impl Iterator for SourceTokenIter<'_> {
+    type Item = SourceToken;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let iter = &mut self.iter;
+
+        let token = match iter.next()? {
             '/' if matches!(iter.peek(0), Some('/')) => {
                 // line comment
                 iter.next(); // consume '/'
@@ -1279,27 +1933,37 @@ pub fn load_inline_config_from_single_file_application_source(
                         break;
                     }
                 }
+
+                SourceToken::LineComment
             }
             '/' if matches!(iter.peek(0), Some('*')) => {
                 // block comment
                 iter.next(); // consume '*'
-
-                if let Some(comment_text) = parse_block_comment(&mut iter) {
-                    let trimmed_text = comment_text.trim();
-                    if trimmed_text.starts_with(INLINE_CONFIG_MARK) {
-                        let (_, config_text) = trimmed_text.split_at("config!".len());
-                        let module_config = ason::from_str(config_text)
-                            .map_err(|e| RuntimeError::Message(e.with_source(config_text)))?;
-                        return Ok(Some(module_config));
+                SourceToken::BlockComment(parse_block_comment(iter))
+            }
+            '"' => {
+                // string
+                while let Some(c) = iter.next() {
+                    match c {
+                        '\\' => {
+                            iter.next(); // consume the escaped char
+                        }
+                        '"' => {
+                            break;
+                        }
+                        _ => {
+                            // consume
+                        }
                     }
                 }
+
+                SourceToken::String
             }
-            _ => {
-                // consume
-            }
-        }
+            c => SourceToken::Char(c),
+        };
+
+        Some(token)
     }
-    Ok(None)
 }
 
 fn parse_block_comment(iter: &mut PeekableIter<char>) -> Option<String> {
@@ -1351,18 +2015,30 @@ fn parse_block_comment(iter: &mut PeekableIter<char>) -> Option<String> {
 
 #[cfg(test)]
 mod tests {
-    use std::path::PathBuf;
+    use std::{
+        fs::File,
+        path::PathBuf,
+        time::{Duration, SystemTime},
+    };
 
-    use anc_image::DEPENDENCY_HASH_ZERO;
+    use anc_image::{entry::ModuleLocation, DEPENDENCY_HASH_ZERO};
     use anc_isa::ModuleDependencyType;
     use resolve_path::PathResolveExt;
 
     use crate::{
         builder::{build_application_by_dependency_tree, load_or_build_module},
         entry::RuntimeProperty,
+        RuntimeError, BUILTIN_MODULE_NAME_STD,
     };
 
-    use super::{build_application_by_single_file, build_module};
+    use super::{
+        build_application_by_single_file, build_module, evict_single_file_application_cache,
+        get_single_file_application_hash_string, is_builtin_module_installed,
+        is_source_code_complete, list_benchmark_function_names_from_single_file_application_source,
+        list_executable_unit_names_from_single_file_application_source,
+        load_inline_config_from_single_file_application_source,
+        load_or_build_application_by_single_file, remove_shebang_line, StableHasher,
+    };
 
     fn get_resources_path_buf() -> PathBuf {
         // `std::env::current_dir()` returns the current Rust project's root folder
@@ -1561,5 +2237,371 @@ mod tests {
             assert!(result0.is_ok());
             // todo: check entries
         }
+
+        // with `seal` flag
+        {
+            let mut script_file_path_buf = get_resources_path_buf();
+            script_file_path_buf.push("single_file_app");
+            script_file_path_buf.push("with_seal.anca");
+
+            let result0 = build_application_by_single_file(
+                &script_file_path_buf,
+                &runtime_property,
+                &mut output,
+            );
+
+            assert!(result0.is_err());
+        }
+
+        // with share module
+        {
+            let mut script_file_path_buf = get_resources_path_buf();
+            script_file_path_buf.push("single_file_app");
+            script_file_path_buf.push("with_share_module.anca");
+
+            let result0 = build_application_by_single_file(
+                &script_file_path_buf,
+                &runtime_property,
+                &mut output,
+            );
+
+            assert!(matches!(
+                result0,
+                Err(RuntimeError::Message(message)) if message.contains("\"digest\"")
+            ));
+        }
+
+        // with executable units
+        {
+            let mut script_file_path_buf = get_resources_path_buf();
+            script_file_path_buf.push("single_file_app");
+            script_file_path_buf.push("with_units.anca");
+
+            let (_, index_entry, _) = build_application_by_single_file(
+                &script_file_path_buf,
+                &runtime_property,
+                &mut output,
+            )
+            .unwrap();
+
+            let mut unit_names = index_entry
+                .entry_point_entries
+                .iter()
+                .map(|entry_point_entry| entry_point_entry.unit_name.as_str())
+                .collect::<Vec<_>>();
+            unit_names.sort();
+
+            assert_eq!(unit_names, vec!["_start", "bar", "foo"]);
+        }
+    }
+
+    #[test]
+    fn test_load_or_build_application_by_single_file() {
+        let mut output: Vec<u8> = vec![];
+
+        let runtime_property = get_runtime_property();
+
+        let mut script_file_path_buf = get_resources_path_buf();
+        script_file_path_buf.push("single_file_app");
+        script_file_path_buf.push("with_conf.anca");
+
+        // build and save to the cache
+        let (_, _, image_binary0) = load_or_build_application_by_single_file(
+            &script_file_path_buf,
+            &runtime_property,
+            &mut output,
+        )
+        .unwrap();
+
+        // load from the cache
+        let (_, index_entry1, image_binary1) = load_or_build_application_by_single_file(
+            &script_file_path_buf,
+            &runtime_property,
+            &mut output,
+        )
+        .unwrap();
+
+        assert_eq!(image_binary0, image_binary1);
+
+        // the application module, "mm_base", "mm_medium" and
+        // the builtin module "std" if it is installed.
+        let std_installed = is_builtin_module_installed(BUILTIN_MODULE_NAME_STD, &runtime_property);
+        assert_eq!(
+            index_entry1.dynamic_link_module_entries.len(),
+            if std_installed { 4 } else { 3 }
+        );
+
+        // the image built without the cache is the same
+        let (_, _, image_binary2) =
+            build_application_by_single_file(&script_file_path_buf, &runtime_property, &mut output)
+                .unwrap();
+
+        assert_eq!(image_binary0, image_binary2);
+    }
+
+    #[test]
+    fn test_build_application_by_single_file_with_std() {
+        let mut output: Vec<u8> = vec![];
+
+        let mut script_file_path_buf = get_resources_path_buf();
+        script_file_path_buf.push("single_file_app");
+        script_file_path_buf.push("no_conf.anca");
+
+        let runtime_home = PathBuf::from("~/.anc").try_resolve().unwrap().to_path_buf();
+
+        // the builtin module `std` is a dependency implicitly if it is installed
+        {
+            let mut runtime_path_buf = get_resources_path_buf();
+            runtime_path_buf.push("runtime_with_std");
+            let runtime_property = RuntimeProperty::from_custom(&runtime_path_buf, &runtime_home);
+
+            let (_, index_entry, _) = build_application_by_single_file(
+                &script_file_path_buf,
+                &runtime_property,
+                &mut output,
+            )
+            .unwrap();
+
+            let dynamic_link_module_entries = &index_entry.dynamic_link_module_entries;
+            assert_eq!(dynamic_link_module_entries.len(), 2);
+            assert_eq!(dynamic_link_module_entries[0].name, "no_conf");
+            assert_eq!(dynamic_link_module_entries[1].name, "std");
+            assert!(matches!(
+                dynamic_link_module_entries[1].module_location.as_ref(),
+                ModuleLocation::Runtime
+            ));
+        }
+
+        // and it is not a dependency otherwise
+        {
+            let mut runtime_path_buf = get_resources_path_buf();
+            runtime_path_buf.push("runtime_without_std");
+            let runtime_property = RuntimeProperty::from_custom(&runtime_path_buf, &runtime_home);
+
+            let (_, index_entry, _) = build_application_by_single_file(
+                &script_file_path_buf,
+                &runtime_property,
+                &mut output,
+            )
+            .unwrap();
+
+            let dynamic_link_module_entries = &index_entry.dynamic_link_module_entries;
+            assert_eq!(dynamic_link_module_entries.len(), 1);
+            assert_eq!(dynamic_link_module_entries[0].name, "no_conf");
+        }
+    }
+
+    #[test]
+    fn test_get_single_file_application_hash_string() {
+        let runtime_property = get_runtime_property();
+
+        let mut folder_path_buf = get_resources_path_buf();
+        folder_path_buf.push("single_file_app");
+
+        let source_code = "fn _start() -> i32 { imm_i32(0) }";
+
+        let hash0 = get_single_file_application_hash_string(
+            &folder_path_buf.join("foo.anca"),
+            source_code,
+            &runtime_property,
+        );
+
+        let hash1 = get_single_file_application_hash_string(
+            &folder_path_buf.join("foo.anca"),
+            source_code,
+            &runtime_property,
+        );
+
+        // the file name is the module name when there is no inline configuration,
+        // so the files with the same source code have different images.
+        let hash2 = get_single_file_application_hash_string(
+            &folder_path_buf.join("bar.anca"),
+            source_code,
+            &runtime_property,
+        );
+
+        assert_eq!(hash0, hash1);
+        assert_ne!(hash0, hash2);
+    }
+
+    #[test]
+    fn test_stable_hasher() {
+        // the test vectors of the 128-bit FNV-1a hash
+        assert_eq!(
+            StableHasher::new().finish(),
+            0x6c62272e07bb014262b821756295c58d
+        );
+
+        let mut hasher = StableHasher::new();
+        hasher.write(b"a");
+        assert_eq!(hasher.finish(), 0xd228cb696f1a8caf78912b704e4a8964);
+
+        let mut hasher = StableHasher::new();
+        hasher.write(b"foobar");
+        assert_eq!(hasher.finish(), 0x343e1662793c64bf6f0d3597ba446f18);
+
+        // the boundaries of parts are kept
+        let mut hasher0 = StableHasher::new();
+        hasher0.write_part(b"ab");
+        hasher0.write_part(b"c");
+
+        let mut hasher1 = StableHasher::new();
+        hasher1.write_part(b"a");
+        hasher1.write_part(b"bc");
+
+        assert_ne!(hasher0.finish(), hasher1.finish());
+    }
+
+    #[test]
+    fn test_evict_single_file_application_cache() {
+        let cache_directory =
+            std::env::temp_dir().join(format!("ancrt-test-evict-cache-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&cache_directory);
+        std::fs::create_dir_all(&cache_directory).unwrap();
+
+        let base_time = SystemTime::now() - Duration::from_secs(60);
+
+        // "0.anci" is the least recently used one
+        for index in 0..4u64 {
+            let file_path = cache_directory.join(format!("{}.anci", index));
+            let file = File::create(&file_path).unwrap();
+            file.set_modified(base_time + Duration::from_secs(index))
+                .unwrap();
+        }
+
+        // the other files are kept
+        std::fs::write(cache_directory.join("readme.txt"), "").unwrap();
+
+        evict_single_file_application_cache(&cache_directory, 2).unwrap();
+
+        let mut file_names = std::fs::read_dir(&cache_directory)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .collect::<Vec<_>>();
+        file_names.sort();
+
+        assert_eq!(file_names, vec!["2.anci", "3.anci", "readme.txt"]);
+
+        std::fs::remove_dir_all(&cache_directory).unwrap();
+    }
+
+    #[test]
+    fn test_is_source_code_complete() {
+        assert!(is_source_code_complete("1"));
+        assert!(is_source_code_complete("fn foo() -> i32 { 1 }"));
+        assert!(!is_source_code_complete("fn foo() -> i32 {"));
+        assert!(!is_source_code_complete("fn foo() -> i32 {\n  add_i32(1,"));
+        assert!(is_source_code_complete(
+            "fn foo() -> i32 {\n  add_i32(1,\n 2)\n}"
+        ));
+
+        // brackets in comments and strings
+        assert!(is_source_code_complete("1 // {"));
+        assert!(is_source_code_complete("1 /* { */"));
+        assert!(is_source_code_complete("1 /* { /* nested */ [ */"));
+        assert!(is_source_code_complete("data s:byte[] = \"{(\\\"\""));
+        assert!(!is_source_code_complete("{ // }"));
+
+        // unterminated block comments
+        assert!(!is_source_code_complete("1 /* {"));
+        assert!(!is_source_code_complete("1 /* /* */"));
+    }
+
+    #[test]
+    fn test_remove_shebang_line() {
+        assert_eq!(
+            remove_shebang_line("#!/usr/bin/env ancrt run\nfn _start() -> i32 {}"),
+            "\nfn _start() -> i32 {}"
+        );
+        assert_eq!(
+            remove_shebang_line("#!/usr/bin/env ancrt run\r\n\nfn _start() -> i32 {}"),
+            "\n\nfn _start() -> i32 {}"
+        );
+        assert_eq!(remove_shebang_line("#!/usr/bin/env ancrt run"), "");
+
+        // not shebang
+        assert_eq!(
+            remove_shebang_line("fn _start() -> i32 {}"),
+            "fn _start() -> i32 {}"
+        );
+        assert_eq!(remove_shebang_line(" #!abc\n"), " #!abc\n");
+        assert_eq!(remove_shebang_line("// #!abc\n"), "// #!abc\n");
+
+        // the comment marks in the shebang line are not comments, the `/*` used to
+        // open a block comment which hid the inline configuration and the units.
+        let source_code = r#"#!/usr/bin/env -S ancrt run --unit /*
+/*
+@config
+{
+    name: "with_shebang"
+    version: "1.0.0"
+    edition: "2025"
+}
+*/
+pub fn _start_foo() -> i32 {}
+"#;
+
+        assert_eq!(
+            load_inline_config_from_single_file_application_source(source_code)
+                .unwrap()
+                .map(|module_config| module_config.name),
+            Some("with_shebang".to_owned())
+        );
+
+        assert_eq!(
+            list_executable_unit_names_from_single_file_application_source(source_code),
+            vec!["foo"]
+        );
+    }
+
+    #[test]
+    fn test_list_executable_unit_names_from_single_file_application_source() {
+        assert_eq!(
+            list_executable_unit_names_from_single_file_application_source(
+                r#"
+fn _start() -> i32 {}
+pub fn _start_foo() -> i32 {}
+pub fn _start_bar()->i32 {}
+fn _start_baz() -> i32 {}
+pub fn start_qux() -> i32 {}
+pub fn _start_() -> i32 {}
+"#
+            ),
+            vec!["foo", "bar"]
+        );
+
+        // in comments and strings
+        assert_eq!(
+            list_executable_unit_names_from_single_file_application_source(
+                r#"
+// pub fn _start_foo() -> i32 {}
+/*
+ pub fn _start_bar() -> i32 {}
+ /* pub fn _start_baz() -> i32 {} */
+*/
+data msg:byte[] = "pub fn _start_qux() \" pub fn _start_quux()"
+pub fn _start_corge() -> i32 {}
+"#
+            ),
+            vec!["corge"]
+        );
+    }
+
+    #[test]
+    fn test_list_benchmark_function_names_from_single_file_application_source() {
+        assert_eq!(
+            list_benchmark_function_names_from_single_file_application_source(
+                r#"
+fn _start() -> i32 {}
+pub fn bench_sort() -> i32 {}
+// pub fn bench_foo() -> i32 {}
+fn bench_bar() -> i32 {}
+pub fn benchmark() -> i32 {}
+pub fn bench_() -> i32 {}
+pub fn bench_search()->i32 {}
+"#
+            ),
+            vec!["bench_sort", "bench_search"]
+        );
     }
 }
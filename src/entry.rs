@@ -17,9 +17,9 @@ use resolve_path::PathResolveExt;
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    RuntimeError, DIRECTORY_NAME_BIN, DIRECTORY_NAME_MODULES, DIRECTORY_NAME_REGISTRIES,
-    DIRECTORY_NAME_REPOSITORIES, DIRECTORY_NAME_RUNTIMES, FILE_NAME_DEFAULT_CONFIG,
-    FILE_NAME_USER_CONFIG,
+    RuntimeError, DIRECTORY_NAME_BIN, DIRECTORY_NAME_CACHE, DIRECTORY_NAME_MODULES,
+    DIRECTORY_NAME_REGISTRIES, DIRECTORY_NAME_REPOSITORIES, DIRECTORY_NAME_RUNTIMES,
+    FILE_NAME_DEFAULT_CONFIG, FILE_NAME_USER_CONFIG,
 };
 
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
@@ -47,6 +47,52 @@ pub struct ModuleConfig {
     /// the default value is []
     #[serde(default)]
     pub libraries: HashMap<String, ExternalLibraryDependency>,
+
+    /// Optional
+    /// the default arguments and environment variables of unit tests
+    #[serde(default)]
+    pub test: TestConfig,
+
+    /// Optional
+    /// the limits of running the application
+    #[serde(default)]
+    pub runtime: RuntimeLimitConfig,
+}
+
+#[derive(Debug, PartialEq, Clone, Default, Serialize, Deserialize)]
+pub struct TestConfig {
+    /// Optional
+    /// the default value is []
+    #[serde(default)]
+    pub arguments: Vec<String>,
+
+    /// Optional
+    /// the default value is []
+    #[serde(default)]
+    pub environments: HashMap<String, String>,
+}
+
+#[derive(Debug, PartialEq, Clone, Default, Serialize, Deserialize)]
+pub struct RuntimeLimitConfig {
+    /// Optional
+    /// the time limit in seconds, no limit by default
+    #[serde(default)]
+    pub timeout: Option<u64>,
+
+    /// Optional
+    /// the memory limit in MiB, no limit by default
+    #[serde(default)]
+    pub max_memory: Option<u64>,
+
+    /// Optional
+    /// the maximum number of threads, no limit by default
+    #[serde(default)]
+    pub max_threads: Option<usize>,
+
+    /// Optional
+    /// the maximum number of executed instructions, no limit by default
+    #[serde(default)]
+    pub max_instructions: Option<u64>,
 }
 
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
@@ -282,6 +328,11 @@ impl RuntimeProperty {
         self.runtime_home.join(DIRECTORY_NAME_MODULES)
     }
 
+    // `{runtime_home}/cache`
+    pub fn get_cache_directory(&self) -> PathBuf {
+        self.runtime_home.join(DIRECTORY_NAME_CACHE)
+    }
+
     // `{launcher_path}/runtimes/EDITION/modules`
     pub fn get_builtin_modules_directory(&self) -> PathBuf {
         self.runtime_path.join(DIRECTORY_NAME_MODULES)
@@ -320,3 +371,53 @@ impl FileMeta {
             .map(Some)
     }
 }
+
+/// The results of benchmarks that the later runs compare against.
+#[derive(Debug, PartialEq, Clone, Default, Serialize, Deserialize)]
+pub struct BenchmarkBaseline {
+    /// the default value is []
+    #[serde(default)]
+    pub items: Vec<BenchmarkBaselineItem>,
+}
+
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct BenchmarkBaselineItem {
+    // the name path of benchmark, e.g. "benches::bench_sort"
+    pub name: String,
+
+    // the elapsed time in nanoseconds
+    pub mean: u64,
+    pub median: u64,
+    pub deviation: u64,
+}
+
+impl BenchmarkBaseline {
+    pub fn load(baseline_file_path: &Path) -> Result<Option<BenchmarkBaseline>, RuntimeError> {
+        let baseline_file = match File::open(baseline_file_path) {
+            Ok(f) => f,
+            Err(e) if e.kind() == ErrorKind::NotFound => {
+                return Ok(None);
+            }
+            Err(e) => {
+                return Err(RuntimeError::Message(format!("{}", e)));
+            }
+        };
+
+        ason::from_reader(baseline_file)
+            .map_err(|e| {
+                RuntimeError::Message(match std::fs::read_to_string(baseline_file_path) {
+                    Ok(source_code) => e.with_source(&source_code),
+                    Err(e) => format!("{}", e),
+                })
+            })
+            .map(Some)
+    }
+
+    pub fn save(&self, baseline_file_path: &Path) -> Result<(), RuntimeError> {
+        let mut baseline_file = File::create(baseline_file_path)
+            .map_err(|e| RuntimeError::Message(format!("{}", e)))?;
+
+        ason::to_writer(self, &mut baseline_file)
+            .map_err(|e| RuntimeError::Message(format!("{}", e)))
+    }
+}
@@ -26,9 +26,13 @@ pub const DIRECTORY_NAME_RUNTIMES: &str = "runtimes";
 pub const DIRECTORY_NAME_REGISTRIES: &str = "registries";
 pub const DIRECTORY_NAME_REPOSITORIES: &str = "repositories";
 pub const DIRECTORY_NAME_MODULES: &str = "modules";
+pub const DIRECTORY_NAME_CACHE: &str = "cache";
 pub const DIRECTORY_NAME_VERSION_REMOTE: &str = "remote";
 pub const DIRECTORY_NAME_VERSION_LOCAL: &str = "local";
 
+// builtin modules
+pub const BUILTIN_MODULE_NAME_STD: &str = "std";
+
 // source files
 pub const FILE_EXTENSION_SOURCE: &str = "anc";
 pub const FILE_EXTENSION_IR: &str = "ancr";
@@ -41,6 +45,8 @@ pub const FILE_NAME_MODULE_CONFIG: &str = "module.anc.ason";
 pub const DIRECTORY_NAME_SRC: &str = "src";
 pub const DIRECTORY_NAME_APP: &str = "app";
 pub const DIRECTORY_NAME_TESTS: &str = "tests";
+pub const DIRECTORY_NAME_INTEGRATION: &str = "integration";
+pub const DIRECTORY_NAME_BENCHES: &str = "benches";
 pub const DIRECTORY_NAME_OUTPUT: &str = "output";
 
 // building asset - files
@@ -49,6 +55,9 @@ pub const FILE_EXTENSION_MODULE: &str = "ancm";
 pub const FILE_EXTENSION_IMAGE: &str = "anci";
 pub const FILE_EXTENSION_META: &str = "meta.ason";
 
+// building asset - the baseline of benchmarks
+pub const FILE_NAME_BENCHMARK_BASELINE: &str = "benchmark.baseline.ason";
+
 // building asset - folders
 pub const DIRECTORY_NAME_IR: &str = "ir";
 pub const DIRECTORY_NAME_ASSEMBLY: &str = "assembly";
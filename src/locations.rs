@@ -13,10 +13,11 @@ use anc_image::{
 
 use crate::{
     entry::RuntimeProperty, DIRECTORY_NAME_APP, DIRECTORY_NAME_ASSEMBLY, DIRECTORY_NAME_ASSET,
-    DIRECTORY_NAME_IR, DIRECTORY_NAME_OBJECT, DIRECTORY_NAME_OUTPUT, DIRECTORY_NAME_SRC,
-    DIRECTORY_NAME_TESTS, DIRECTORY_NAME_VERSION_REMOTE, FILE_EXTENSION_ASSEMBLY,
-    FILE_EXTENSION_IMAGE, FILE_EXTENSION_IR, FILE_EXTENSION_META, FILE_EXTENSION_MODULE,
-    FILE_EXTENSION_OBJECT, FILE_NAME_MODULE_CONFIG,
+    DIRECTORY_NAME_BENCHES, DIRECTORY_NAME_INTEGRATION, DIRECTORY_NAME_IR, DIRECTORY_NAME_OBJECT,
+    DIRECTORY_NAME_OUTPUT, DIRECTORY_NAME_SRC, DIRECTORY_NAME_TESTS, DIRECTORY_NAME_VERSION_REMOTE,
+    FILE_EXTENSION_ASSEMBLY, FILE_EXTENSION_IMAGE, FILE_EXTENSION_IR, FILE_EXTENSION_META,
+    FILE_EXTENSION_MODULE, FILE_EXTENSION_OBJECT, FILE_NAME_BENCHMARK_BASELINE,
+    FILE_NAME_MODULE_CONFIG,
 };
 
 pub fn get_shared_module_image_file_path_by_dynamic_link_module_entry(
@@ -92,6 +93,17 @@ pub fn get_application_module_image_file_path(module_path: &Path, module_name: &
     path_buf
 }
 
+/// Returns `{runtime_home}/cache/{hash}.anci`
+pub fn get_single_file_application_cache_file_path(
+    cache_directory: &Path,
+    hash_string: &str,
+) -> PathBuf {
+    let mut path_buf = PathBuf::from(cache_directory);
+    path_buf.push(hash_string);
+    path_buf.set_extension(FILE_EXTENSION_IMAGE);
+    path_buf
+}
+
 /// Returns `{module_folder}/module.anc.ason`
 pub fn get_module_config_file_path(module_path: &Path) -> PathBuf {
     let mut path_buf = PathBuf::from(module_path);
@@ -120,6 +132,20 @@ pub fn get_module_folder_tests_path(module_path: &Path) -> PathBuf {
     path_buf
 }
 
+/// `{module_folder}/integration`
+pub fn get_module_folder_integration_path(module_path: &Path) -> PathBuf {
+    let mut path_buf = PathBuf::from(module_path);
+    path_buf.push(DIRECTORY_NAME_INTEGRATION);
+    path_buf
+}
+
+/// `{module_folder}/benches`
+pub fn get_module_folder_benches_path(module_path: &Path) -> PathBuf {
+    let mut path_buf = PathBuf::from(module_path);
+    path_buf.push(DIRECTORY_NAME_BENCHES);
+    path_buf
+}
+
 /// `{module_folder}/output`
 pub fn get_module_folder_output_path(module_path: &Path) -> PathBuf {
     let mut path_buf = PathBuf::from(module_path);
@@ -127,6 +153,13 @@ pub fn get_module_folder_output_path(module_path: &Path) -> PathBuf {
     path_buf
 }
 
+/// `{module_folder}/output/benchmark.baseline.ason`
+pub fn get_benchmark_baseline_file_path(module_path: &Path) -> PathBuf {
+    let mut path_buf = get_module_folder_output_path(module_path);
+    path_buf.push(FILE_NAME_BENCHMARK_BASELINE);
+    path_buf
+}
+
 /// - `{module_folder}/output/{hash}`
 /// - `{module_folder}/output`
 pub fn get_output_folder_hash_path(
@@ -4,15 +4,19 @@
 // the Mozilla Public License version 2.0 and additional exceptions,
 // more details in file LICENSE, LICENSE.additional and CONTRIBUTING.
 
-use std::{collections::HashMap, io::Write, path::PathBuf};
+use std::{collections::HashMap, fs::File, io::Write, path::PathBuf, time::Duration};
 
 use anc_isa::ModuleDependencyType;
 use anc_runtime::{
-    builder::build_application_by_dependency_tree,
+    builder::{build_application_by_dependency_tree, is_source_code_complete},
     entry::RuntimeProperty,
     runner::{
-        launch_application, launch_single_file_application, launch_unit_tests,
-        EXECUTABLE_UNIT_NAME_SEPARATOR,
+        install_backtrace_panic_hook, launch_application_with_limits, launch_benchmarks,
+        launch_integration_tests, launch_single_file_application_with_limits, launch_unit_tests,
+        list_entry_points, list_integration_tests, list_unit_tests, serve_program_process,
+        write_entry_points, write_unit_test_report, BenchmarkOptions, EntryPointListFormat,
+        ProgramAllocator, Repl, ReplOutput, RuntimeLimits, UnitTestExpectation, UnitTestFilter,
+        UnitTestOptions, UnitTestReportFormat, UnitTestStatus, EXECUTABLE_UNIT_NAME_SEPARATOR,
     },
     RuntimeError,
 };
@@ -26,6 +30,38 @@ enum Commands {
         #[arg(short, long)]
         unit_name: Option<String>,
 
+        /// Time limit of the application, overrides the `runtime` section of the module configuration
+        #[arg(long, value_name = "seconds")]
+        timeout: Option<u64>,
+
+        /// Memory limit of the application, overrides the `runtime` section of the module configuration
+        #[arg(long, value_name = "MiB")]
+        max_memory: Option<u64>,
+
+        /// Threads limit of the application, overrides the `runtime` section of the module configuration
+        #[arg(long, value_name = "N")]
+        max_threads: Option<usize>,
+
+        /// Instructions limit of the application, overrides the `runtime` section of the module configuration
+        #[arg(long, value_name = "N")]
+        max_instructions: Option<u64>,
+
+        /// Rebuild the single-file application instead of loading it from the cache
+        #[arg(long)]
+        no_cache: bool,
+
+        /// List the executable units of the application without running it
+        #[arg(long)]
+        list_units: bool,
+
+        /// List the executable units and unit tests of the application without running it
+        #[arg(long, conflicts_with = "list_units")]
+        list: bool,
+
+        /// Format of the list
+        #[arg(long, value_enum, default_value = "text")]
+        list_format: ListFormat,
+
         /// Path to application
         application_path: Option<String>,
 
@@ -48,11 +84,84 @@ enum Commands {
     },
     /// Run unit tests
     Test {
-        /// Prefix of path name of the test units.
+        /// Prefix of path name of the test units, or a pattern with the wildcards `*` and `?`.
+        path_name_prefix: Option<String>,
+
+        /// Path to module
+        module_path: Option<PathBuf>,
+
+        /// Run the unit tests that match this pattern, can be specified multiple times
+        #[arg(long, value_name = "pattern")]
+        include: Vec<String>,
+
+        /// Skip the unit tests that match this pattern, can be specified multiple times
+        #[arg(long, value_name = "pattern")]
+        skip: Vec<String>,
+
+        /// Match the name path of the unit tests exactly instead of by prefix
+        #[arg(long)]
+        exact: bool,
+
+        /// Run only the ignored unit tests
+        #[arg(long)]
+        ignored: bool,
+
+        /// List the selected unit tests without running them
+        #[arg(long)]
+        list: bool,
+
+        /// Run the integration tests in the folder `integration` instead of the unit tests
+        #[arg(long)]
+        integration: bool,
+
+        /// Number of unit tests running at the same time, defaults to the number of CPUs
+        #[arg(long)]
+        threads: Option<usize>,
+
+        /// Time limit of each unit test
+        #[arg(long, value_name = "seconds")]
+        timeout: Option<u64>,
+
+        /// Show the output of all unit tests instead of capturing it
+        #[arg(long)]
+        nocapture: bool,
+
+        /// Write a structured test report
+        #[arg(long)]
+        #[arg(value_enum)]
+        format: Option<ReportFormat>,
+
+        /// File to save the test report, defaults to the standard output
+        #[arg(long, requires = "format")]
+        output: Option<PathBuf>,
+
+        /// Arguments passed to each unit test, e.g. `ancrt test -- --fixtures data`
+        #[arg(last = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+    /// Run benchmarks
+    Bench {
+        /// Prefix of path name of the benchmarks, or a pattern with the wildcards `*` and `?`.
         path_name_prefix: Option<String>,
 
         /// Path to module
         module_path: Option<PathBuf>,
+
+        /// Number of measured runs of each benchmark
+        #[arg(long, default_value_t = 10)]
+        iterations: usize,
+
+        /// Number of runs before measuring
+        #[arg(long, default_value_t = 3)]
+        warm_up: usize,
+
+        /// Save the results as the baseline for later runs to compare against
+        #[arg(long)]
+        save_baseline: bool,
+
+        /// Arguments passed to each benchmark
+        #[arg(last = true, allow_hyphen_values = true)]
+        args: Vec<String>,
     },
     /// Build the module
     Build {
@@ -117,6 +226,8 @@ enum Commands {
         /// Path to application
         application_path: Option<PathBuf>,
     },
+    /// Start an interactive session for assembly functions and expressions
+    Repl,
     /// Launch a text editor
     Edit { file: PathBuf },
     /// Launch a Shell
@@ -152,6 +263,18 @@ enum NewModuleType {
     Script,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum ReportFormat {
+    Json,
+    Junit,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum ListFormat {
+    Text,
+    Json,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
 enum ShellType {
     Bash,
@@ -220,7 +343,16 @@ struct Cli {
     command: Commands,
 }
 
+#[global_allocator]
+static GLOBAL: ProgramAllocator = ProgramAllocator;
+
 fn main() {
+    install_backtrace_panic_hook();
+
+    // run the program requested by the runtime if this is a child process,
+    // e.g. a unit test.
+    serve_program_process();
+
     if let Err(err) = process_cmd() {
         let mut stderr = std::io::stderr();
         let message = match err {
@@ -240,6 +372,14 @@ fn process_cmd() -> Result<(), RuntimeError> {
     match cli.command {
         Commands::Run {
             unit_name,
+            timeout,
+            max_memory,
+            max_threads,
+            max_instructions,
+            no_cache,
+            list_units,
+            list,
+            list_format,
             application_path,
             args,
         } => {
@@ -268,30 +408,58 @@ fn process_cmd() -> Result<(), RuntimeError> {
                 inline_unit_name
             };
 
+            // the executable unit name is passed to the runner in the
+            // form of ":unit_name", see `launch_application` for details.
+            let executable_unit_name = if actual_unit_name.is_empty() {
+                String::new()
+            } else {
+                format!("{}{}", EXECUTABLE_UNIT_NAME_SEPARATOR, actual_unit_name)
+            };
+
             let module_path = PathBuf::from(module_path_string);
             let full_path = module_path.canonicalize().unwrap();
 
-            let exit_code = if full_path.is_file() {
-                if !actual_unit_name.is_empty() {
-                    return Err(RuntimeError::Message(
-                        "Single-file application does not support specify the executable unit name."
-                            .to_owned(),
-                    ));
-                }
+            if list_units || list {
+                // `--list-units` lists the executable units only
+                let entry_points = list_entry_points(&full_path, list, &mut std::io::stderr())
+                    .map_err(|err| RuntimeError::Message(format!("{}", err)))?;
+
+                let format = match list_format {
+                    ListFormat::Text => EntryPointListFormat::Text,
+                    ListFormat::Json => EntryPointListFormat::Json,
+                };
+
+                write_entry_points(format, &entry_points, &mut std::io::stdout())
+                    .map_err(|err| RuntimeError::Message(format!("{}", err)))?;
+
+                return Ok(());
+            }
 
-                launch_single_file_application(
+            let runtime_limits = RuntimeLimits {
+                timeout: timeout.map(Duration::from_secs),
+                max_memory: max_memory.map(|mib| mib * 1024 * 1024),
+                max_threads,
+                max_instructions,
+            };
+
+            let exit_code = if full_path.is_file() {
+                launch_single_file_application_with_limits(
                     &full_path,
+                    &executable_unit_name,
                     args,
                     HashMap::<String, String>::new(),
+                    &runtime_limits,
+                    !no_cache,
                     &mut std::io::stderr(),
                 )
                 .map_err(|err| RuntimeError::Message(format!("{}", err)))?
             } else {
-                launch_application(
+                launch_application_with_limits(
                     &full_path,
-                    &actual_unit_name,
+                    &executable_unit_name,
                     args,
                     HashMap::<String, String>::new(),
+                    &runtime_limits,
                     &mut std::io::stderr(),
                 )
                 .map_err(|err| RuntimeError::Message(format!("{}", err)))?
@@ -307,6 +475,18 @@ fn process_cmd() -> Result<(), RuntimeError> {
         Commands::Test {
             path_name_prefix,
             module_path,
+            include,
+            skip,
+            exact,
+            ignored,
+            list,
+            integration,
+            threads,
+            timeout,
+            nocapture,
+            format,
+            output,
+            args,
         } => {
             let path = if let Some(path) = module_path {
                 path
@@ -322,36 +502,217 @@ fn process_cmd() -> Result<(), RuntimeError> {
                 ));
             }
 
-            let prefix = if let Some(prefix) = path_name_prefix {
-                prefix
+            let mut patterns = vec![];
+            if let Some(prefix) = path_name_prefix {
+                patterns.push(prefix);
+            }
+            patterns.extend(include);
+
+            let unit_test_filter = UnitTestFilter {
+                patterns,
+                skip_patterns: skip,
+                exact,
+                ignored,
+            };
+
+            if list {
+                let mut logger = std::io::stdout();
+                let (unit_test_names, filter_out_names) = if integration {
+                    list_integration_tests(&full_path, &unit_test_filter)
+                } else {
+                    list_unit_tests(&full_path, &unit_test_filter, &mut logger)
+                }
+                .map_err(|err| RuntimeError::Message(format!("{}", err)))?;
+
+                for unit_test_name in &unit_test_names {
+                    println!("{}", unit_test_name);
+                }
+
+                println!();
+                println!(
+                    "{} tests, {} filtered out.",
+                    unit_test_names.len(),
+                    filter_out_names.len()
+                );
+
+                return Ok(());
+            }
+
+            let mut options = UnitTestOptions::default();
+            if let Some(threads) = threads {
+                options.threads = threads;
+            }
+            options.timeout = timeout.map(Duration::from_secs);
+            options.capture_output = !nocapture;
+
+            // the progress and summary are written to the standard error
+            // when the report is written to the standard output.
+            let mut logger: Box<dyn Write> = if format.is_some() && output.is_none() {
+                Box::new(std::io::stderr())
             } else {
-                String::new()
+                Box::new(std::io::stdout())
+            };
+
+            let (unit_test_results, filter_out_names) = if integration {
+                launch_integration_tests(
+                    &full_path,
+                    &unit_test_filter,
+                    args,
+                    HashMap::<String, String>::new(),
+                    &options,
+                    &mut logger,
+                )
+            } else {
+                launch_unit_tests(
+                    &full_path,
+                    &unit_test_filter,
+                    args,
+                    HashMap::<String, String>::new(),
+                    &options,
+                    &mut logger,
+                )
+            }
+            .map_err(|err| RuntimeError::Message(format!("{}", err)))?;
+
+            let failed_results = unit_test_results
+                .iter()
+                .filter(|item| !item.success())
+                .collect::<Vec<_>>();
+
+            if !failed_results.is_empty() {
+                writeln!(logger).unwrap();
+                writeln!(logger, "Failures:").unwrap();
+                for failed_result in &failed_results {
+                    match &failed_result.status {
+                        UnitTestStatus::Exited(exit_code) => match failed_result.expectation() {
+                            UnitTestExpectation::Success => writeln!(
+                                logger,
+                                "    \"{}\": exit code {}",
+                                failed_result.name, exit_code
+                            ),
+                            expectation => writeln!(
+                                logger,
+                                "    \"{}\": exit code {}, expected {}",
+                                failed_result.name, exit_code, expectation
+                            ),
+                        },
+                        UnitTestStatus::Error(message) => {
+                            writeln!(logger, "    \"{}\": error: {}", failed_result.name, message)
+                        }
+                        UnitTestStatus::TimedOut => {
+                            writeln!(logger, "    \"{}\": timed out", failed_result.name)
+                        }
+                    }
+                    .unwrap();
+                }
+            }
+
+            writeln!(logger).unwrap();
+            writeln!(
+                logger,
+                "Test result: {} passed, {} failed, {} filtered out.",
+                unit_test_results.len() - failed_results.len(),
+                failed_results.len(),
+                filter_out_names.len()
+            )
+            .unwrap();
+
+            if let Some(format) = format {
+                let report_format = match format {
+                    ReportFormat::Json => UnitTestReportFormat::Json,
+                    ReportFormat::Junit => UnitTestReportFormat::JUnit,
+                };
+
+                if let Some(output_file_path) = output {
+                    let mut file = File::create(&output_file_path)
+                        .map_err(|e| RuntimeError::Message(format!("{}", e)))?;
+                    write_unit_test_report(
+                        report_format,
+                        &unit_test_results,
+                        &filter_out_names,
+                        &mut file,
+                    )
+                } else {
+                    write_unit_test_report(
+                        report_format,
+                        &unit_test_results,
+                        &filter_out_names,
+                        &mut stdout,
+                    )
+                }
+                .map_err(|e| RuntimeError::Message(format!("{}", e)))?;
+            }
+
+            if failed_results.is_empty() {
+                Ok(())
+            } else {
+                Err(RuntimeError::Message(format!(
+                    "{} unit test(s) failed.",
+                    failed_results.len()
+                )))
+            }
+        }
+        Commands::Bench {
+            path_name_prefix,
+            module_path,
+            iterations,
+            warm_up,
+            save_baseline,
+            args,
+        } => {
+            let path = if let Some(path) = module_path {
+                path
+            } else {
+                PathBuf::from(".")
             };
 
-            let mut stdout = std::io::stdout();
+            let full_path = path.canonicalize().unwrap();
+
+            if full_path.is_file() {
+                return Err(RuntimeError::Message(
+                    "Single-file application does not support benchmark.".to_owned(),
+                ));
+            }
+
+            let unit_test_filter = UnitTestFilter {
+                patterns: path_name_prefix.into_iter().collect(),
+                ..UnitTestFilter::default()
+            };
 
-            let (unit_test_results, filter_out_names) = launch_unit_tests(
+            let options = BenchmarkOptions {
+                warm_up_iterations: warm_up,
+                iterations,
+                save_baseline,
+            };
+
+            let (benchmark_results, benchmark_failures, filter_out_names) = launch_benchmarks(
                 &full_path,
-                &prefix,
-                vec![],
+                &unit_test_filter,
+                args,
                 HashMap::<String, String>::new(),
+                &options,
                 &mut stdout,
             )
             .map_err(|err| RuntimeError::Message(format!("{}", err)))?;
 
-            let pass_count = unit_test_results.iter().filter(|item| item.success).count();
-
             writeln!(stdout).unwrap();
             writeln!(
                 stdout,
-                "Test result: {} passed, {} failed, {} filtered out.",
-                pass_count,
-                unit_test_results.len() - pass_count,
+                "Bench result: {} measured, {} failed, {} filtered out.",
+                benchmark_results.len(),
+                benchmark_failures.len(),
                 filter_out_names.len()
             )
             .unwrap();
 
-            Ok(())
+            if benchmark_failures.is_empty() {
+                Ok(())
+            } else {
+                Err(RuntimeError::Message(format!(
+                    "{} benchmark(s) failed.",
+                    benchmark_failures.len()
+                )))
+            }
         }
         Commands::Build { module_path, tests } => {
             let path = if let Some(path) = module_path {
@@ -393,7 +754,64 @@ fn process_cmd() -> Result<(), RuntimeError> {
         Commands::Env { name: _ } => todo!(),
         Commands::Debug {
             application_path: _,
-        } => todo!(),
+        } => {
+            // todo:: the debugger, see the document `design_notes.md`.
+            Err(RuntimeError::Message(
+                "The debugger is not supported by the current processor yet.".to_owned(),
+            ))
+        }
+        Commands::Repl => {
+            let mut repl = Repl::new(&std::env::current_dir().unwrap())
+                .map_err(|err| RuntimeError::Message(format!("{}", err)))?;
+
+            println!("Enter definitions (functions, data and imports) or expressions of type i32.");
+            println!("Type \":reset\" to remove all definitions, \":quit\" or Ctrl+D to exit.");
+
+            let stdin = std::io::stdin();
+            let mut input = String::new();
+
+            loop {
+                print!("{}", if input.is_empty() { "> " } else { "... " });
+                stdout.flush().unwrap();
+
+                let mut line = String::new();
+                if stdin.read_line(&mut line).unwrap() == 0 {
+                    // EOF
+                    println!();
+                    break;
+                }
+
+                if input.is_empty() {
+                    match line.trim() {
+                        "" => continue,
+                        ":quit" => break,
+                        ":reset" => {
+                            repl.reset();
+                            continue;
+                        }
+                        _ => {}
+                    }
+                }
+
+                input.push_str(&line);
+
+                // an input of multiple lines is complete when all
+                // brackets are closed and all block comments are terminated.
+                if !is_source_code_complete(&input) {
+                    continue;
+                }
+
+                match repl.eval(&input, &mut std::io::sink()) {
+                    Ok(ReplOutput::Defined) => {}
+                    Ok(ReplOutput::Value(value)) => println!("{}", value),
+                    Err(err) => eprintln!("{}", err),
+                }
+
+                input.clear();
+            }
+
+            Ok(())
+        }
         Commands::Edit { file: _ } => todo!(),
         Commands::Shell { command_line: _ } => todo!(),
         Commands::Command { command_line: _ } => todo!(),
@@ -4,14 +4,33 @@
 // the Mozilla Public License version 2.0 and additional exceptions,
 // more details in file LICENSE, LICENSE.additional and CONTRIBUTING.
 
-use std::{collections::HashMap, fs::File, io::Write, path::Path, sync::Mutex};
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    any::Any,
+    backtrace::Backtrace,
+    cell::RefCell,
+    collections::HashMap,
+    fmt::Display,
+    fs::File,
+    io::Write,
+    ops::Deref,
+    panic::AssertUnwindSafe,
+    path::{Path, PathBuf},
+    process::{Child, Command, Stdio},
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
 
 use anc_context::{
     external_function_table::ExternalFunctionTable, process_context::ProcessContext,
     process_property::ProcessProperty, process_resource::ProcessResource,
 };
 use anc_image::{
-    entry::{EntryPointEntry, ImageIndexEntry},
+    entry::{EntryPointEntry, ImageCommonEntry, ImageIndexEntry},
+    entry_writer::write_image_file,
     module_image::ModuleImage,
     ImageError,
 };
@@ -20,16 +39,102 @@ use anc_linker::DEFAULT_ENTRY_FUNCTION_NAME;
 use anc_parser_asm::NAME_PATH_SEPARATOR;
 use anc_processor::{multithread_process::start_program, GenericError};
 use memmap2::Mmap;
+use serde::{Deserialize, Serialize};
 
 use crate::{
-    builder::{build_application_by_dependency_tree, build_application_by_single_file},
-    entry::RuntimeProperty,
-    locations::get_shared_module_image_file_path_by_dynamic_link_module_entry,
+    builder::{
+        build_application_by_dependency_tree, build_application_by_single_file,
+        build_application_by_single_file_in_module, build_application_by_source_code,
+        list_benchmark_function_names_from_single_file_application_source,
+        load_inline_config_from_single_file_application_source,
+        load_or_build_application_by_single_file, EXECUTABLE_UNIT_FUNCTION_NAME_PREFIX,
+    },
+    entry::{
+        BenchmarkBaseline, BenchmarkBaselineItem, ModuleConfig, RuntimeLimitConfig,
+        RuntimeProperty, TestConfig,
+    },
+    locations::{
+        get_benchmark_baseline_file_path, get_module_config_file_path,
+        get_module_folder_benches_path, get_module_folder_integration_path,
+        get_module_folder_output_path,
+        get_shared_module_image_file_path_by_dynamic_link_module_entry,
+    },
+    source_scanner::{list_assembly_files, PathAndTimestamp},
     RuntimeError,
 };
 
 pub const EXECUTABLE_UNIT_NAME_SEPARATOR: &str = ":";
 
+/// The exit code of the application that exceeds the time limit,
+/// it is the same as the command `timeout` of GNU coreutils.
+pub const EXIT_CODE_TIME_LIMIT_EXCEEDED: u32 = 124;
+
+/// The exit code of the application that exceeds the memory limit.
+pub const EXIT_CODE_MEMORY_LIMIT_EXCEEDED: u32 = 123;
+
+/// The exit code of the application that exceeds the threads limit.
+pub const EXIT_CODE_THREADS_LIMIT_EXCEEDED: u32 = 122;
+
+/// The limits of running an application, the limits that are not specified
+/// are taken from the `runtime` section of the module configuration.
+///
+/// The application runs in a child process when any limit is specified,
+/// the child process is stopped as soon as a limit is exceeded, and the
+/// application exits with the exit code of the limit.
+///
+/// The memory is limited by the operating system (`RLIMIT_DATA`, so it is
+/// available on Unix only), the threads are checked every
+/// `PROGRAM_PROCESS_POLL_INTERVAL` through the `/proc` file system, so they
+/// are not checked on the systems without it.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct RuntimeLimits {
+    /// The application is stopped and exits with `EXIT_CODE_TIME_LIMIT_EXCEEDED`
+    /// if it runs longer than this.
+    pub timeout: Option<Duration>,
+
+    /// The data memory (i.e. the heap and the stacks) of the application can not grow
+    /// beyond this (in bytes), the memory of the runtime is included.
+    ///
+    /// The allocation of the runtime (e.g. the memory of the VM) fails and the application
+    /// exits with `EXIT_CODE_MEMORY_LIMIT_EXCEEDED` when the limit is reached, while the
+    /// allocation of the external functions (e.g. the `malloc()` of libc) returns NULL
+    /// as usual.
+    pub max_memory: Option<u64>,
+
+    /// The application is stopped and exits with `EXIT_CODE_THREADS_LIMIT_EXCEEDED`
+    /// if it has more threads than this, the threads of the runtime are included.
+    pub max_threads: Option<usize>,
+
+    /// The maximum number of the executed instructions.
+    ///
+    /// todo:: it has to be counted by the processor, an error is returned
+    /// when it is specified for now.
+    pub max_instructions: Option<u64>,
+}
+
+impl RuntimeLimits {
+    /// The specified limits take precedence over the ones in the configuration.
+    fn merge_config(&self, runtime_config: &RuntimeLimitConfig) -> Self {
+        Self {
+            timeout: self
+                .timeout
+                .or(runtime_config.timeout.map(Duration::from_secs)),
+            max_memory: self
+                .max_memory
+                .or(runtime_config.max_memory.map(|mib| mib * 1024 * 1024)),
+            max_threads: self.max_threads.or(runtime_config.max_threads),
+            max_instructions: self.max_instructions.or(runtime_config.max_instructions),
+        }
+    }
+
+    fn is_unlimited(&self) -> bool {
+        self.timeout.is_none()
+            && self.max_memory.is_none()
+            && self.max_threads.is_none()
+            && self.max_instructions.is_none()
+    }
+}
+
 /// executable_unit_name:
 ///
 /// - internal entry point name: "_start"
@@ -45,6 +150,26 @@ pub fn launch_application(
     arguments: Vec<String>,                // program arguments
     environments: HashMap<String, String>, // environment variables (Key-value pairs)
     logger: &mut dyn Write,
+) -> Result<u32, GenericError> {
+    launch_application_with_limits(
+        module_path,
+        executable_unit_name,
+        arguments,
+        environments,
+        &RuntimeLimits::default(),
+        logger,
+    )
+}
+
+/// The same as `launch_application`, but with the specified limits,
+/// see `RuntimeLimits` for details.
+pub fn launch_application_with_limits(
+    module_path: &Path,
+    executable_unit_name: &str,
+    arguments: Vec<String>,                // program arguments
+    environments: HashMap<String, String>, // environment variables (Key-value pairs)
+    runtime_limits: &RuntimeLimits,
+    logger: &mut dyn Write,
 ) -> Result<u32, GenericError> {
     let runtime_property = RuntimeProperty::from_runtime_exec_file()?;
 
@@ -53,43 +178,236 @@ pub fn launch_application(
         std::fs::create_dir_all(runtime_home).unwrap();
     }
 
-    let (image_files, _) = load_application(module_path, &runtime_property, false, logger)?;
+    let (image_file_paths, _) = load_application(module_path, &runtime_property, false, logger)?;
+
+    let module_config_file_path = get_module_config_file_path(module_path);
+    let runtime_limits =
+        runtime_limits.merge_config(&ModuleConfig::load(&module_config_file_path)?.runtime);
 
     // create process
 
-    let process_property = ProcessProperty {
+    let program = Program {
+        main_image_data_opt: None,
+        image_file_paths,
         application_path: module_path.to_path_buf(),
         is_script: false,
         arguments,
         environments,
     };
 
-    let entry_point_name = if executable_unit_name.is_empty() {
-        DEFAULT_ENTRY_FUNCTION_NAME.to_owned()
+    let entry_point_name = get_entry_point_name_by_executable_unit_name(executable_unit_name)?;
+    execute_with_limits(program, &entry_point_name, &runtime_limits, logger)
+}
+
+/// Convert the CLI executable unit name to the internal entry point name,
+/// e.g. "" -> "_start", ":foo" -> "foo".
+fn get_entry_point_name_by_executable_unit_name(
+    executable_unit_name: &str,
+) -> Result<String, RuntimeError> {
+    if executable_unit_name.is_empty() {
+        Ok(DEFAULT_ENTRY_FUNCTION_NAME.to_owned())
     } else if let Some(name) = executable_unit_name.strip_prefix(EXECUTABLE_UNIT_NAME_SEPARATOR) {
-        name.to_owned()
+        Ok(name.to_owned())
     } else {
-        return Err(Box::new(RuntimeError::Message(
+        Err(RuntimeError::Message(
             "Incorrect entry point name.".to_owned(),
-        )));
+        ))
+    }
+}
+
+/// List the executable units of an application, which can be a module
+/// or a single-file application.
+///
+/// Returns the internal entry point names, e.g. `["_start", "foo", "bar"]`,
+/// the unit tests are excluded, see `launch_application` for the
+/// relationship between the entry point names and the CLI unit names.
+pub fn list_executable_units(
+    application_path: &Path,
+    logger: &mut dyn Write,
+) -> Result<Vec<String>, GenericError> {
+    let ApplicationEntryPoints {
+        default_entry_point,
+        executable_units,
+        ..
+    } = list_entry_points(application_path, false, logger)?;
+
+    // the default entry point first
+    Ok(default_entry_point
+        .into_iter()
+        .chain(executable_units)
+        .collect())
+}
+
+/// The entry points of an application, all names are the internal
+/// entry point names, see `launch_application` for details.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct ApplicationEntryPoints {
+    /// The default entry point "_start", it is `None` if the
+    /// application has no function `_start`.
+    pub default_entry_point: Option<String>,
+
+    /// The additional executable units, e.g. "foo".
+    pub executable_units: Vec<String>,
+
+    /// The unit tests, e.g. "foo::test_add".
+    pub unit_tests: Vec<String>,
+}
+
+impl ApplicationEntryPoints {
+    pub fn from_entry_point_entries(entry_point_entries: Vec<EntryPointEntry>) -> Self {
+        let mut entry_points = ApplicationEntryPoints::default();
+
+        for entry_point_entry in entry_point_entries {
+            let name = entry_point_entry.unit_name;
+            if name == DEFAULT_ENTRY_FUNCTION_NAME {
+                entry_points.default_entry_point = Some(name);
+            } else if name.contains(NAME_PATH_SEPARATOR) {
+                entry_points.unit_tests.push(name);
+            } else {
+                entry_points.executable_units.push(name);
+            }
+        }
+
+        entry_points.executable_units.sort();
+        entry_points.unit_tests.sort();
+        entry_points
+    }
+}
+
+/// List the entry points of an application, which can be a module or
+/// a single-file application. The unit tests are listed only if
+/// `include_unit_tests` is true, and a single-file application has
+/// no unit tests.
+pub fn list_entry_points(
+    application_path: &Path,
+    include_unit_tests: bool,
+    logger: &mut dyn Write,
+) -> Result<ApplicationEntryPoints, GenericError> {
+    let runtime_property = RuntimeProperty::from_runtime_exec_file()?;
+
+    let entry_point_entries = if application_path.is_file() {
+        let (_, _, entry_point_entries) =
+            load_single_file_application(application_path, &runtime_property, true, logger)?;
+        entry_point_entries
+    } else {
+        let (_, entry_point_entries) = load_application(
+            application_path,
+            &runtime_property,
+            include_unit_tests,
+            logger,
+        )?;
+        entry_point_entries
     };
 
-    execute_unit(&image_files, &entry_point_name, process_property)
+    Ok(ApplicationEntryPoints::from_entry_point_entries(
+        entry_point_entries,
+    ))
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum EntryPointListFormat {
+    Text,
+    Json,
+}
+
+/// Write the entry points of an application, the executable units
+/// are written as the CLI unit names, e.g. ":foo".
+///
+/// The format `Text` is:
+///
+/// ```text
+/// _start (default)
+/// :foo
+/// :bar
+/// foo::test_add (test)
+/// ```
+///
+/// The format `Json` is:
+///
+/// ```json
+/// {
+///     "default": "_start",
+///     "units": [
+///         ":bar",
+///         ":foo"
+///     ],
+///     "tests": [
+///         "foo::test_add"
+///     ]
+/// }
+/// ```
+///
+/// where "default" is `null` if the application has no default entry point.
+pub fn write_entry_points(
+    format: EntryPointListFormat,
+    entry_points: &ApplicationEntryPoints,
+    writer: &mut dyn Write,
+) -> std::io::Result<()> {
+    let unit_names = entry_points
+        .executable_units
+        .iter()
+        .map(|name| format!("{}{}", EXECUTABLE_UNIT_NAME_SEPARATOR, name))
+        .collect::<Vec<_>>();
+
+    match format {
+        EntryPointListFormat::Text => {
+            if let Some(name) = &entry_points.default_entry_point {
+                writeln!(writer, "{} (default)", name)?;
+            }
+
+            for unit_name in &unit_names {
+                writeln!(writer, "{}", unit_name)?;
+            }
+
+            for unit_test_name in &entry_points.unit_tests {
+                writeln!(writer, "{} (test)", unit_test_name)?;
+            }
+        }
+        EntryPointListFormat::Json => {
+            #[derive(Serialize)]
+            struct EntryPointsReport<'a> {
+                default: Option<&'a str>,
+                units: &'a [String],
+                tests: &'a [String],
+            }
+
+            write_json(
+                &EntryPointsReport {
+                    default: entry_points.default_entry_point.as_deref(),
+                    units: &unit_names,
+                    tests: &entry_points.unit_tests,
+                },
+                writer,
+            )?;
+        }
+    }
+
+    Ok(())
 }
 
-/// unit_test_name_path_prefix
+/// unit_test_filter
 ///
 /// - internal entry point name: "{submodule_name}::test_*"
 ///   executes function: '{app_module_name}::tests::{submodule_name}::test_*' (unit tests)
-///   user CLI unit name: name path prefix, e.g. "{submodule_name}", "{submodule_name}::test_get_"
+///   user CLI unit name: name path pattern, e.g. "{submodule_name}", "{submodule_name}::test_get_",
+///   "*::test_get_*", see `UnitTestFilter` for details.
 ///
-/// Returns `(Vec<UnitTestResult>, filter_out_names: Vec<String>)`
+/// The arguments and environment variables declared in the `test` section
+/// of the module configuration are passed to each unit test first, then
+/// the specified `arguments` are appended, and the specified `environments`
+/// override the declared ones with the same name.
+///
+/// Unit tests are run concurrently, see `UnitTestOptions` for details.
+///
+/// Returns `(Vec<UnitTestResult>, filter_out_names: Vec<String>)`, the
+/// `filter_out_names` includes the ignored unit tests.
 pub fn launch_unit_tests(
     module_path: &Path,
-    unit_test_name_path_prefix: &str,
+    unit_test_filter: &UnitTestFilter,
     arguments: Vec<String>,                // program arguments
     environments: HashMap<String, String>, // environment variables
     // extra_registries: Vec<String>,
+    options: &UnitTestOptions,
     logger: &mut dyn Write,
 ) -> Result<(Vec<UnitTestResult>, Vec<String>), GenericError> {
     let runtime_property = RuntimeProperty::from_runtime_exec_file()?;
@@ -99,72 +417,90 @@ pub fn launch_unit_tests(
         std::fs::create_dir_all(runtime_home).unwrap();
     }
 
-    let (image_files, entry_point_entries) =
+    let (image_file_paths, entry_point_entries) =
         load_application(module_path, &runtime_property, true, logger)?;
 
-    let process_property = ProcessProperty {
-        application_path: module_path.to_path_buf(),
-        is_script: false,
+    let module_config_file_path = get_module_config_file_path(module_path);
+    let (test_arguments, test_environments) = merge_test_arguments_and_environments(
+        ModuleConfig::load(&module_config_file_path)?.test,
         arguments,
         environments,
-    };
-
-    // unit test
-    let mut entry_point_names = vec![];
+    );
 
-    let mut unit_test_results = vec![];
-    let mut filter_out_names: Vec<String> = vec![];
+    let program = Arc::new(Program {
+        main_image_data_opt: None,
+        image_file_paths,
+        application_path: module_path.to_path_buf(),
+        is_script: false,
+        arguments: test_arguments,
+        environments: test_environments,
+    });
 
-    for entry_point_entry in &entry_point_entries {
-        let entry_point_name = &entry_point_entry.unit_name;
-        if entry_point_name.contains(NAME_PATH_SEPARATOR) {
-            if entry_point_name.starts_with(unit_test_name_path_prefix) {
-                entry_point_names.push(entry_point_name);
-            } else {
-                filter_out_names.push(entry_point_name.to_owned());
-            }
-        }
-    }
+    // unit test
+    let (entry_point_names, filter_out_names) =
+        select_unit_tests(&entry_point_entries, unit_test_filter);
 
     writeln!(logger).unwrap();
     writeln!(logger, "Running {} unit test(s)", entry_point_names.len())?;
 
-    for entry_point_name in entry_point_names {
-        let result = execute_unit(&image_files, entry_point_name, process_property.clone())?;
-        let success = result == 0;
-        writeln!(
-            logger,
-            "Test \"{entry_point_name}\": {}",
-            if success { "ok" } else { "FAILED" }
-        )?;
+    let unit_tests = entry_point_names
+        .into_iter()
+        .map(|entry_point_name| UnitTestItem {
+            name: entry_point_name.clone(),
+            entry_point_name,
+            program_result: Ok(Arc::clone(&program)),
+        })
+        .collect::<Vec<_>>();
 
-        unit_test_results.push(UnitTestResult {
-            name: entry_point_name.to_owned(),
-            success,
-        });
-    }
+    let unit_test_results = execute_unit_tests(unit_tests, options, logger)?;
 
     Ok((unit_test_results, filter_out_names))
 }
 
-#[derive(Debug, PartialEq)]
-pub struct UnitTestResult {
-    pub name: String,
-    pub success: bool,
-}
+/// Merge the arguments and environment variables declared in the `test` section
+/// of the module configuration with the specified ones, the specified `arguments`
+/// are appended, and the specified `environments` override the declared ones
+/// with the same name.
+fn merge_test_arguments_and_environments(
+    test_config: TestConfig,
+    arguments: Vec<String>,
+    environments: HashMap<String, String>,
+) -> (Vec<String>, HashMap<String, String>) {
+    let TestConfig {
+        arguments: mut test_arguments,
+        environments: mut test_environments,
+    } = test_config;
 
-impl UnitTestResult {
-    pub fn new(name: String, success: bool) -> Self {
-        Self { name, success }
-    }
+    test_arguments.extend(arguments);
+    test_environments.extend(environments);
+
+    (test_arguments, test_environments)
 }
 
-pub fn launch_single_file_application(
-    script_file_path: &Path,
+/// Run the integration tests of a module.
+///
+/// Each assembly file in the folder `{module_folder}/integration` is an integration test,
+/// it is built as a single-file application that links the module as a dependency
+/// (see `build_application_by_single_file_in_module`), and it passes when its `_start`
+/// function returns `0`.
+///
+/// The name of an integration test is the name path of the file prefixed with "integration",
+/// e.g. "integration::network::http" for the file "integration/network/http.anca", the
+/// `unit_test_filter`, the `test` section of the module configuration and the
+/// `options` are applied the same as unit tests.
+///
+/// A test file that fails to build is reported as an error of the test
+/// (i.e. `UnitTestStatus::Error`), and the remaining tests keep running.
+///
+/// Returns `(Vec<UnitTestResult>, filter_out_names: Vec<String>)`
+pub fn launch_integration_tests(
+    module_path: &Path,
+    unit_test_filter: &UnitTestFilter,
     arguments: Vec<String>,                // program arguments
     environments: HashMap<String, String>, // environment variables
+    options: &UnitTestOptions,
     logger: &mut dyn Write,
-) -> Result<u32, GenericError> {
+) -> Result<(Vec<UnitTestResult>, Vec<String>), GenericError> {
     let runtime_property = RuntimeProperty::from_runtime_exec_file()?;
 
     let runtime_home = &runtime_property.runtime_home;
@@ -172,248 +508,3451 @@ pub fn launch_single_file_application(
         std::fs::create_dir_all(runtime_home).unwrap();
     }
 
-    let (main_image_data, image_files, _entry_point_entries) =
-        load_single_file_application(script_file_path, &runtime_property, logger)?;
+    let module_config_file_path = get_module_config_file_path(module_path);
+    let ModuleConfig {
+        name: module_name,
+        test: test_config,
+        ..
+    } = ModuleConfig::load(&module_config_file_path)?;
 
-    // create process
+    let (test_arguments, test_environments) =
+        merge_test_arguments_and_environments(test_config, arguments, environments);
 
-    let process_property = ProcessProperty {
-        application_path: script_file_path.to_path_buf(),
-        is_script: true,
-        arguments,
-        environments,
-    };
+    let (integration_tests, filter_out_names) = select_single_file_applications(
+        module_path,
+        &get_module_folder_integration_path(module_path),
+        unit_test_filter,
+    )?;
 
-    execute_script(main_image_data, &image_files, process_property)
-}
+    let mut unit_tests = vec![];
 
-/// internal entry point names:
-///
-/// - internal entry point name: "_start"
-///   executes function: '{app_module_name}::_start' (the default entry point)
-///   user CLI unit name: "" (empty string)
-///
-/// - internal entry point name: "{submodule_name}"
-///   executes function: '{app_module_name}::app::{submodule_name}::_start' (the additional executable units)
-///   user CLI unit name: ":{submodule_name}"
-///
-/// - internal entry point name: "{submodule_name}::test_*"
-///   executes function: '{app_module_name}::tests::{submodule_name}::test_*' (unit tests)
-///   user CLI unit name: name path prefix, e.g. "{submodule_name}", "{submodule_name}::test_get_"
-fn execute_unit(
-    image_files: &[File],
-    internal_entry_point_name: &str,
-    process_property: ProcessProperty,
-) -> Result<u32, GenericError> {
-    let mut mapped_files = vec![];
+    for (name, test_file_path) in integration_tests {
+        let program_result = load_single_file_application_in_module(
+            &test_file_path,
+            module_path,
+            &module_name,
+            false,
+            &runtime_property,
+            logger,
+        )
+        .map(|(main_image_data, image_file_paths, _)| {
+            Arc::new(Program {
+                main_image_data_opt: Some(main_image_data),
+                image_file_paths,
+                application_path: test_file_path,
+                is_script: true,
+                arguments: test_arguments.clone(),
+                environments: test_environments.clone(),
+            })
+        })
+        .map_err(|e| format!("Failed to build the test file: {}", e));
 
-    for image_file in image_files {
-        let mmap = unsafe { Mmap::map(image_file).expect("Failed to map the image file.") };
-        mapped_files.push(mmap);
+        unit_tests.push(UnitTestItem {
+            name,
+            entry_point_name: DEFAULT_ENTRY_FUNCTION_NAME.to_owned(),
+            program_result,
+        });
     }
 
-    let resource = MappedFileProcessResource::new(mapped_files, process_property);
-    let process_context = resource.create_process_context()?;
-    start_program(&process_context, internal_entry_point_name, vec![])
-}
-
-fn execute_script(
-    main_image_data: Vec<u8>,
-    image_files: &[File],
-    process_property: ProcessProperty,
-) -> Result<u32, GenericError> {
-    let mut mapped_files = vec![];
+    writeln!(logger).unwrap();
+    writeln!(logger, "Running {} integration test(s)", unit_tests.len())?;
 
-    for image_file in image_files {
-        let mmap = unsafe { Mmap::map(image_file).expect("Failed to map the image file.") };
-        mapped_files.push(mmap);
-    }
+    let unit_test_results = execute_unit_tests(unit_tests, options, logger)?;
 
-    let resource = ScriptFileProcessResource::new(main_image_data, mapped_files, process_property);
-    let process_context = resource.create_process_context()?;
-    start_program(&process_context, DEFAULT_ENTRY_FUNCTION_NAME, vec![])
+    Ok((unit_test_results, filter_out_names))
 }
 
-fn load_application(
+/// Run the benchmarks of a module.
+///
+/// Each assembly file in the folder `{module_folder}/benches` is built as a single-file
+/// application that links the module as a dependency (the same as integration tests),
+/// and each function `pub fn bench_*() -> i32` of the file is a benchmark, it is run
+/// repeatedly and fails if it returns a non-zero exit code.
+///
+/// The name of a benchmark is the name path of the file prefixed with "benches" and
+/// followed by the function name, e.g. "benches::sort::bench_quick_sort" for the
+/// function `bench_quick_sort` in the file "benches/sort.anca".
+///
+/// A failed benchmark (including the benchmarks of a file that fails to build) is
+/// reported and recorded in the returned failures, and the remaining benchmarks
+/// keep running.
+///
+/// The results are compared against the baseline saved in the output folder,
+/// see `BenchmarkOptions` for details.
+///
+/// Returns `(Vec<BenchmarkResult>, Vec<BenchmarkFailure>, filter_out_names: Vec<String>)`
+pub fn launch_benchmarks(
     module_path: &Path,
-    runtime_property: &RuntimeProperty,
-    include_unit_tests: bool,
+    unit_test_filter: &UnitTestFilter,
+    arguments: Vec<String>,                // program arguments
+    environments: HashMap<String, String>, // environment variables
+    options: &BenchmarkOptions,
     logger: &mut dyn Write,
-) -> Result<(Vec<File>, Vec<EntryPointEntry>), RuntimeError> {
-    let (_, index_entry, application_image_file_full_path) = build_application_by_dependency_tree(
+) -> Result<(Vec<BenchmarkResult>, Vec<BenchmarkFailure>, Vec<String>), GenericError> {
+    let runtime_property = RuntimeProperty::from_runtime_exec_file()?;
+
+    let runtime_home = &runtime_property.runtime_home;
+    if !runtime_home.exists() {
+        std::fs::create_dir_all(runtime_home).unwrap();
+    }
+
+    let module_config_file_path = get_module_config_file_path(module_path);
+    let module_name = ModuleConfig::load(&module_config_file_path)?.name;
+
+    // the filter is applied to the name of each benchmark function
+    // instead of the file.
+    let (bench_files, _) = select_single_file_applications(
         module_path,
-        ModuleDependencyType::Local,
-        runtime_property,
-        include_unit_tests,
-        logger,
+        &get_module_folder_benches_path(module_path),
+        &UnitTestFilter::default(),
     )?;
 
-    let mut image_file_paths = vec![application_image_file_full_path];
+    let baseline_file_path = get_benchmark_baseline_file_path(module_path);
+    let baseline_opt = BenchmarkBaseline::load(&baseline_file_path)?;
 
-    for dynamic_link_module_entry in &index_entry.dynamic_link_module_entries[1..] {
-        let image_file_path = get_shared_module_image_file_path_by_dynamic_link_module_entry(
-            dynamic_link_module_entry,
-            runtime_property,
-        );
-        image_file_paths.push(image_file_path);
-    }
+    let mut benchmark_results = vec![];
+    let mut benchmark_failures = vec![];
+    let mut filter_out_names = vec![];
 
-    let mut image_files = vec![];
-    for path_buf in &image_file_paths {
-        let file = File::open(path_buf).map_err(|e| RuntimeError::Message(e.to_string()))?;
-        image_files.push(file);
-    }
+    for (file_name, bench_file_path) in bench_files {
+        let source_code = std::fs::read_to_string(&bench_file_path)?;
 
-    let ImageIndexEntry {
-        entry_point_entries,
-        ..
-    } = index_entry;
+        // `Vec<(name, function_name)>`
+        let mut benchmarks = vec![];
+        for function_name in
+            list_benchmark_function_names_from_single_file_application_source(&source_code)
+        {
+            let name = format!("{}{}{}", file_name, NAME_PATH_SEPARATOR, function_name);
+            if unit_test_filter.is_match(&name) {
+                benchmarks.push((name, function_name));
+            } else {
+                filter_out_names.push(name);
+            }
+        }
 
-    Ok((image_files, entry_point_entries))
-}
+        if benchmarks.is_empty() {
+            continue;
+        }
 
-fn load_single_file_application(
-    script_file_path: &Path,
-    runtime_property: &RuntimeProperty,
-    logger: &mut dyn Write,
-) -> Result<(Vec<u8>, Vec<File>, Vec<EntryPointEntry>), RuntimeError> {
-    let (_, index_entry, main_image_data) =
-        build_application_by_single_file(script_file_path, runtime_property, logger)?;
+        let (main_image_data, image_file_paths, _) = match load_single_file_application_in_module(
+            &bench_file_path,
+            module_path,
+            &module_name,
+            true,
+            &runtime_property,
+            logger,
+        ) {
+            Ok(value) => value,
+            Err(e) => {
+                for (name, _) in benchmarks {
+                    let message = format!("Failed to build the benchmark file: {}", e);
+                    writeln!(logger, "Bench \"{}\": FAILED\n    {}", name, message)?;
+                    benchmark_failures.push(BenchmarkFailure { name, message });
+                }
+                continue;
+            }
+        };
 
-    let mut image_file_paths = vec![];
+        let process_property = ProcessProperty {
+            application_path: bench_file_path,
+            is_script: true,
+            arguments: arguments.clone(),
+            environments: environments.clone(),
+        };
 
-    for dynamic_link_module_entry in &index_entry.dynamic_link_module_entries[1..] {
-        let image_file_path = get_shared_module_image_file_path_by_dynamic_link_module_entry(
-            dynamic_link_module_entry,
-            runtime_property,
-        );
-        image_file_paths.push(image_file_path);
-    }
+        let mapped_files = map_image_files(&image_file_paths)?;
+        let resource =
+            ScriptFileProcessResource::new(main_image_data, mapped_files, process_property);
 
-    let mut image_files = vec![];
-    for path_buf in &image_file_paths {
-        let file = File::open(path_buf).map_err(|e| RuntimeError::Message(e.to_string()))?;
-        image_files.push(file);
-    }
+        for (name, function_name) in benchmarks {
+            let samples = match execute_benchmark(&resource, &function_name, options) {
+                Ok(samples) => samples,
+                Err(e) => {
+                    let message = format!("{}", e);
+                    writeln!(logger, "Bench \"{}\": FAILED\n    {}", name, message)?;
+                    benchmark_failures.push(BenchmarkFailure { name, message });
+                    continue;
+                }
+            };
 
-    let ImageIndexEntry {
-        entry_point_entries,
-        ..
-    } = index_entry;
+            let baseline_mean = baseline_opt
+                .as_ref()
+                .and_then(|baseline| baseline.items.iter().find(|item| item.name == name))
+                .map(|item| Duration::from_nanos(item.mean));
 
-    Ok((main_image_data, image_files, entry_point_entries))
-}
+            let benchmark_result = BenchmarkResult::new(name, &samples, baseline_mean);
 
-pub struct MappedFileProcessResource {
-    mapped_files: Vec<Mmap>,
-    process_property: ProcessProperty,
-    external_function_table: Mutex<ExternalFunctionTable>,
-}
+            write!(
+                logger,
+                "Bench \"{}\": mean {:?}, median {:?}, deviation {:?}",
+                benchmark_result.name,
+                benchmark_result.mean,
+                benchmark_result.median,
+                benchmark_result.deviation
+            )?;
 
-impl MappedFileProcessResource {
-    pub fn new(mapped_files: Vec<Mmap>, process_property: ProcessProperty) -> Self {
-        Self {
-            mapped_files,
-            process_property,
-            external_function_table: Mutex::new(ExternalFunctionTable::default()),
+            match benchmark_result.change() {
+                Some(change) => writeln!(logger, ", change {:+.2}%", change * 100.0)?,
+                None => writeln!(logger)?,
+            }
+
+            benchmark_results.push(benchmark_result);
         }
     }
-}
 
-impl ProcessResource for MappedFileProcessResource {
-    fn create_process_context(&self) -> Result<ProcessContext, ImageError> {
-        let mut module_images = vec![];
+    if options.save_baseline || baseline_opt.is_none() {
+        // keep the baseline of the benchmarks that are filtered out or failed
+        let mut baseline = baseline_opt.unwrap_or_default();
 
-        for file in &self.mapped_files {
-            let module_image = ModuleImage::read(file)?;
-            module_images.push(module_image);
+        for benchmark_result in &benchmark_results {
+            let baseline_item = BenchmarkBaselineItem {
+                name: benchmark_result.name.clone(),
+                mean: benchmark_result.mean.as_nanos() as u64,
+                median: benchmark_result.median.as_nanos() as u64,
+                deviation: benchmark_result.deviation.as_nanos() as u64,
+            };
+
+            match baseline
+                .items
+                .iter()
+                .position(|item| item.name == baseline_item.name)
+            {
+                Some(pos) => baseline.items[pos] = baseline_item,
+                None => baseline.items.push(baseline_item),
+            }
         }
 
-        let process_context = ProcessContext::new(
-            &self.process_property,
-            &self.external_function_table,
-            module_images,
-        );
+        std::fs::create_dir_all(get_module_folder_output_path(module_path))?;
+        baseline.save(&baseline_file_path)?;
 
-        Ok(process_context)
+        writeln!(
+            logger,
+            "Write benchmark baseline: {}",
+            baseline_file_path
+                .strip_prefix(module_path)
+                .unwrap()
+                .to_str()
+                .unwrap()
+        )?;
     }
+
+    Ok((benchmark_results, benchmark_failures, filter_out_names))
 }
 
-pub struct ScriptFileProcessResource {
-    main_image_data: Vec<u8>,
-    mapped_files: Vec<Mmap>,
-    process_property: ProcessProperty,
-    external_function_table: Mutex<ExternalFunctionTable>,
+/// Run the function of a benchmark repeatedly, `internal_entry_point_name`
+/// is the name of the function, see `launch_benchmarks`.
+///
+/// Returns the elapsed time of each measured run, the process context
+/// is created before each run, so the setup is not measured.
+fn execute_benchmark(
+    resource: &ScriptFileProcessResource,
+    internal_entry_point_name: &str,
+    options: &BenchmarkOptions,
+) -> Result<Vec<Duration>, GenericError> {
+    let iterations = options.iterations.max(1);
+    let mut samples = vec![];
+
+    for iteration in 0..(options.warm_up_iterations + iterations) {
+        let process_context = resource.create_process_context()?;
+
+        let start_instant = Instant::now();
+        let exit_code =
+            start_program_with_error_report(&process_context, internal_entry_point_name)?;
+        let duration = start_instant.elapsed();
+
+        if exit_code != 0 {
+            return Err(Box::new(RuntimeError::Message(format!(
+                "The function returned exit code {}.",
+                exit_code
+            ))));
+        }
+
+        // the warm-up runs are not measured
+        if iteration >= options.warm_up_iterations {
+            samples.push(duration);
+        }
+    }
+
+    Ok(samples)
 }
 
-impl ScriptFileProcessResource {
-    pub fn new(
-        main_image_data: Vec<u8>,
-        mapped_files: Vec<Mmap>,
-        process_property: ProcessProperty,
-    ) -> Self {
+#[derive(Debug, PartialEq, Clone)]
+pub struct BenchmarkOptions {
+    /// The number of runs before measuring, e.g. for warming up the caches.
+    pub warm_up_iterations: usize,
+
+    /// The number of measured runs.
+    pub iterations: usize,
+
+    /// Save the results as the baseline, which the later runs compare against.
+    /// The baseline is also saved when it does not exist.
+    pub save_baseline: bool,
+}
+
+impl Default for BenchmarkOptions {
+    fn default() -> Self {
         Self {
-            main_image_data,
-            mapped_files,
-            process_property,
-            external_function_table: Mutex::new(ExternalFunctionTable::default()),
+            warm_up_iterations: 3,
+            iterations: 10,
+            save_baseline: false,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct BenchmarkResult {
+    pub name: String,
+    pub mean: Duration,
+    pub median: Duration,
+
+    /// The standard deviation.
+    pub deviation: Duration,
+
+    /// The mean of the same benchmark in the baseline.
+    pub baseline_mean: Option<Duration>,
+}
+
+impl BenchmarkResult {
+    pub fn new(name: String, samples: &[Duration], baseline_mean: Option<Duration>) -> Self {
+        let mut secs = samples
+            .iter()
+            .map(|sample| sample.as_secs_f64())
+            .collect::<Vec<_>>();
+        secs.sort_by(|left, right| left.total_cmp(right));
+
+        let count = secs.len() as f64;
+        let mean = secs.iter().sum::<f64>() / count;
+
+        let median = if secs.len() % 2 == 0 {
+            (secs[secs.len() / 2 - 1] + secs[secs.len() / 2]) / 2.0
+        } else {
+            secs[secs.len() / 2]
+        };
+
+        let variance = secs
+            .iter()
+            .map(|sec| (sec - mean) * (sec - mean))
+            .sum::<f64>()
+            / count;
+
+        Self {
+            name,
+            mean: Duration::from_secs_f64(mean),
+            median: Duration::from_secs_f64(median),
+            deviation: Duration::from_secs_f64(variance.sqrt()),
+            baseline_mean,
+        }
+    }
+
+    /// The relative change of the mean compared to the baseline,
+    /// e.g. `0.05` means 5% slower, and `-0.05` means 5% faster.
+    pub fn change(&self) -> Option<f64> {
+        self.baseline_mean
+            .filter(|baseline_mean| !baseline_mean.is_zero())
+            .map(|baseline_mean| self.mean.as_secs_f64() / baseline_mean.as_secs_f64() - 1.0)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct BenchmarkFailure {
+    pub name: String,
+    pub message: String,
+}
+
+/// List the names of integration tests without building and running them.
+///
+/// Returns `(integration_test_names: Vec<String>, filter_out_names: Vec<String>)`
+pub fn list_integration_tests(
+    module_path: &Path,
+    unit_test_filter: &UnitTestFilter,
+) -> Result<(Vec<String>, Vec<String>), GenericError> {
+    let (integration_tests, filter_out_names) = select_single_file_applications(
+        module_path,
+        &get_module_folder_integration_path(module_path),
+        unit_test_filter,
+    )?;
+
+    let integration_test_names = integration_tests
+        .into_iter()
+        .map(|(name, _)| name)
+        .collect::<Vec<_>>();
+
+    Ok((integration_test_names, filter_out_names))
+}
+
+/// Select the single-file applications (i.e. integration tests and benchmarks)
+/// in the specified folder of a module.
+///
+/// Returns `(Vec<(name, file_path)>, filter_out_names: Vec<String>)`,
+/// sorted by name.
+fn select_single_file_applications(
+    module_path: &Path,
+    folder_path: &Path,
+    unit_test_filter: &UnitTestFilter,
+) -> Result<(Vec<(String, PathBuf)>, Vec<String>), RuntimeError> {
+    let mut applications = list_assembly_files(folder_path)?
+        .into_iter()
+        .map(|PathAndTimestamp { file_path, .. }| {
+            // "integration/network/http.anca" -> "integration::network::http"
+            let relative_path = file_path
+                .strip_prefix(module_path)
+                .unwrap()
+                .with_extension("");
+            let name = relative_path
+                .components()
+                .map(|component| component.as_os_str().to_str().unwrap())
+                .collect::<Vec<_>>()
+                .join(NAME_PATH_SEPARATOR);
+            (name, file_path)
+        })
+        .collect::<Vec<_>>();
+
+    applications.sort_by(|left, right| left.0.cmp(&right.0));
+
+    let mut selected_applications = vec![];
+    let mut filter_out_names = vec![];
+
+    for (name, file_path) in applications {
+        if unit_test_filter.is_match(&name) {
+            selected_applications.push((name, file_path));
+        } else {
+            filter_out_names.push(name);
+        }
+    }
+
+    Ok((selected_applications, filter_out_names))
+}
+
+/// List the names of unit tests without running them.
+///
+/// Returns `(unit_test_names: Vec<String>, filter_out_names: Vec<String>)`
+pub fn list_unit_tests(
+    module_path: &Path,
+    unit_test_filter: &UnitTestFilter,
+    logger: &mut dyn Write,
+) -> Result<(Vec<String>, Vec<String>), GenericError> {
+    let runtime_property = RuntimeProperty::from_runtime_exec_file()?;
+
+    let runtime_home = &runtime_property.runtime_home;
+    if !runtime_home.exists() {
+        std::fs::create_dir_all(runtime_home).unwrap();
+    }
+
+    let (_, entry_point_entries) = load_application(module_path, &runtime_property, true, logger)?;
+
+    Ok(select_unit_tests(&entry_point_entries, unit_test_filter))
+}
+
+/// The unit test functions whose name starts with this prefix are ignored,
+/// they only run when `UnitTestFilter::ignored` is `true`.
+pub const UNIT_TEST_IGNORED_FUNCTION_NAME_PREFIX: &str = "test_ignore_";
+
+/// Select unit tests by their name paths, e.g. "network::http::test_get".
+///
+/// A pattern containing the wildcard `*` (matches any characters, including
+/// the separator `::`) or `?` (matches a single character) is matched against the
+/// whole name path, otherwise the pattern matches the name paths that start
+/// with it, or only the identical name path if `exact` is `true`.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct UnitTestFilter {
+    /// The unit tests that match any of these patterns are selected,
+    /// all unit tests are selected if it is empty.
+    pub patterns: Vec<String>,
+
+    /// The unit tests that match any of these patterns are excluded.
+    pub skip_patterns: Vec<String>,
+
+    pub exact: bool,
+
+    /// Select the ignored unit tests instead of the normal ones.
+    pub ignored: bool,
+}
+
+impl UnitTestFilter {
+    pub fn is_match(&self, unit_test_name: &str) -> bool {
+        let included = self.patterns.is_empty()
+            || self
+                .patterns
+                .iter()
+                .any(|pattern| self.is_pattern_match(pattern, unit_test_name));
+
+        let skipped = self
+            .skip_patterns
+            .iter()
+            .any(|pattern| self.is_pattern_match(pattern, unit_test_name));
+
+        included && !skipped && is_ignored_unit_test(unit_test_name) == self.ignored
+    }
+
+    fn is_pattern_match(&self, pattern: &str, unit_test_name: &str) -> bool {
+        if pattern.contains(['*', '?']) {
+            let pattern_chars = pattern.chars().collect::<Vec<_>>();
+            let name_chars = unit_test_name.chars().collect::<Vec<_>>();
+            is_glob_match(&pattern_chars, &name_chars)
+        } else if self.exact {
+            unit_test_name == pattern
+        } else {
+            unit_test_name.starts_with(pattern)
+        }
+    }
+}
+
+/// The unit test functions whose name starts with this prefix are expected
+/// to fail, i.e. return a non-zero exit code or abort (e.g. the VM traps).
+pub const UNIT_TEST_SHOULD_FAIL_FUNCTION_NAME_PREFIX: &str = "test_should_fail_";
+
+/// The unit test functions whose name starts with this prefix and a number
+/// followed by `_` are expected to return that exit code,
+/// e.g. "test_exit_2_invalid_argument" is expected to return `2`.
+pub const UNIT_TEST_EXPECTED_EXIT_CODE_FUNCTION_NAME_PREFIX: &str = "test_exit_";
+
+/// The expected result of a unit test, declared by the name of the unit test function.
+///
+/// The ignored prefix can be combined with the others, e.g.
+/// "test_ignore_should_fail_overflow" and "test_ignore_exit_2_invalid_argument".
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum UnitTestExpectation {
+    /// The unit test function returns `0`.
+    Success,
+
+    /// The unit test function returns a non-zero exit code, or aborts.
+    Failure,
+
+    /// The unit test function returns the specified exit code.
+    ExitCode(u32),
+}
+
+impl UnitTestExpectation {
+    pub fn from_unit_test_name(unit_test_name: &str) -> Self {
+        let function_name = get_unit_test_function_name(unit_test_name);
+
+        // "test_ignore_should_fail_foo" -> "test_should_fail_foo"
+        let function_name = match function_name.strip_prefix(UNIT_TEST_IGNORED_FUNCTION_NAME_PREFIX)
+        {
+            Some(rest) => format!("test_{}", rest),
+            None => function_name.to_owned(),
+        };
+
+        if function_name.starts_with(UNIT_TEST_SHOULD_FAIL_FUNCTION_NAME_PREFIX) {
+            return UnitTestExpectation::Failure;
+        }
+
+        if let Some(rest) =
+            function_name.strip_prefix(UNIT_TEST_EXPECTED_EXIT_CODE_FUNCTION_NAME_PREFIX)
+        {
+            let (number, _) = rest.split_once('_').unwrap_or((rest, ""));
+            if let Ok(exit_code) = number.parse::<u32>() {
+                return UnitTestExpectation::ExitCode(exit_code);
+            }
+        }
+
+        UnitTestExpectation::Success
+    }
+
+    pub fn is_satisfied_by(&self, status: &UnitTestStatus) -> bool {
+        match (self, status) {
+            (UnitTestExpectation::Success, UnitTestStatus::Exited(exit_code)) => *exit_code == 0,
+            (UnitTestExpectation::Failure, UnitTestStatus::Exited(exit_code)) => *exit_code != 0,
+            (UnitTestExpectation::Failure, UnitTestStatus::Error(_)) => true,
+            (UnitTestExpectation::ExitCode(expected), UnitTestStatus::Exited(exit_code)) => {
+                exit_code == expected
+            }
+            _ => false,
+        }
+    }
+}
+
+impl Display for UnitTestExpectation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            UnitTestExpectation::Success => f.write_str("exit code 0"),
+            UnitTestExpectation::Failure => f.write_str("a failure"),
+            UnitTestExpectation::ExitCode(exit_code) => write!(f, "exit code {}", exit_code),
+        }
+    }
+}
+
+fn is_ignored_unit_test(unit_test_name: &str) -> bool {
+    get_unit_test_function_name(unit_test_name).starts_with(UNIT_TEST_IGNORED_FUNCTION_NAME_PREFIX)
+}
+
+fn is_glob_match(pattern: &[char], text: &[char]) -> bool {
+    match pattern.split_first() {
+        None => text.is_empty(),
+        Some(('*', pattern_rest)) => {
+            (0..=text.len()).any(|skip| is_glob_match(pattern_rest, &text[skip..]))
+        }
+        Some(('?', pattern_rest)) => !text.is_empty() && is_glob_match(pattern_rest, &text[1..]),
+        Some((c, pattern_rest)) => {
+            text.first() == Some(c) && is_glob_match(pattern_rest, &text[1..])
+        }
+    }
+}
+
+/// Returns `(unit_test_names: Vec<String>, filter_out_names: Vec<String>)`
+fn select_unit_tests(
+    entry_point_entries: &[EntryPointEntry],
+    unit_test_filter: &UnitTestFilter,
+) -> (Vec<String>, Vec<String>) {
+    let mut unit_test_names = vec![];
+    let mut filter_out_names = vec![];
+
+    for entry_point_entry in entry_point_entries {
+        let entry_point_name = &entry_point_entry.unit_name;
+        if entry_point_name.contains(NAME_PATH_SEPARATOR) {
+            if unit_test_filter.is_match(entry_point_name) {
+                unit_test_names.push(entry_point_name.to_owned());
+            } else {
+                filter_out_names.push(entry_point_name.to_owned());
+            }
+        }
+    }
+
+    (unit_test_names, filter_out_names)
+}
+
+pub struct UnitTestOptions {
+    /// The maximum number of unit tests running at the same time.
+    pub threads: usize,
+
+    /// The wall clock time limit of each unit test,
+    /// `None` means no limit.
+    pub timeout: Option<Duration>,
+
+    /// Capture the standard output and standard error of each unit test,
+    /// the output is stored in `UnitTestResult` and shown for the
+    /// failed unit tests only.
+    ///
+    /// Each unit test runs in its own child process, so the output is
+    /// captured separately while the unit tests run concurrently, and
+    /// the standard streams of the runtime are not affected.
+    pub capture_output: bool,
+}
+
+impl Default for UnitTestOptions {
+    fn default() -> Self {
+        let threads = std::thread::available_parallelism()
+            .map(|n| n.get())
+            .unwrap_or(1);
+
+        Self {
+            threads,
+            timeout: None,
+            capture_output: false,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct UnitTestResult {
+    pub name: String,
+    pub status: UnitTestStatus,
+
+    /// The elapsed (wall clock) time of the unit test.
+    pub duration: Duration,
+
+    /// The captured standard output and standard error,
+    /// it is empty if the output capture is disabled.
+    pub output: String,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum UnitTestStatus {
+    /// The unit test function returned the exit code, whether the
+    /// test passed depends on the `UnitTestExpectation`.
+    Exited(u32),
+
+    /// The unit test was aborted, e.g. the VM trapped or the
+    /// process context could not be created.
+    Error(String),
+
+    /// The unit test did not finish within the time limit.
+    TimedOut,
+}
+
+impl UnitTestResult {
+    pub fn new(name: String, status: UnitTestStatus, duration: Duration, output: String) -> Self {
+        Self {
+            name,
+            status,
+            duration,
+            output,
+        }
+    }
+
+    pub fn expectation(&self) -> UnitTestExpectation {
+        UnitTestExpectation::from_unit_test_name(&self.name)
+    }
+
+    /// Returns `true` if the result meets the expectation of the unit test,
+    /// e.g. a "test_should_fail_*" unit test succeeds when it fails.
+    pub fn success(&self) -> bool {
+        self.expectation().is_satisfied_by(&self.status)
+    }
+
+    pub fn exit_code(&self) -> Option<u32> {
+        match self.status {
+            UnitTestStatus::Exited(exit_code) => Some(exit_code),
+            UnitTestStatus::Error(_) | UnitTestStatus::TimedOut => None,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum UnitTestReportFormat {
+    Json,
+    JUnit,
+}
+
+/// Write the results of unit tests as a structured report, the
+/// `filter_out_names` are reported as skipped tests.
+///
+/// The report of format `Json` is:
+///
+/// ```json
+/// {
+///     "passed": 1,
+///     "failed": 0,
+///     "skipped": 1,
+///     "tests": [
+///         {
+///             "name": "foo::test_add",
+///             "submodule": "foo",
+///             "status": "passed",
+///             "exit_code": 0,
+///             "duration": 0.001,
+///             "message": null,
+///             "output": ""
+///         },
+///         {
+///             "name": "bar::test_divide",
+///             "submodule": "bar",
+///             "status": "skipped",
+///             "exit_code": null,
+///             "duration": 0.0,
+///             "message": null,
+///             "output": ""
+///         }
+///     ]
+/// }
+/// ```
+///
+/// where "status" is one of "passed", "failed", "error", "timed_out" and "skipped".
+///
+/// The report of format `JUnit` groups unit tests into a `testsuite`
+/// for each submodule, the captured output is written to `system-out`.
+pub fn write_unit_test_report(
+    format: UnitTestReportFormat,
+    unit_test_results: &[UnitTestResult],
+    filter_out_names: &[String],
+    writer: &mut dyn Write,
+) -> std::io::Result<()> {
+    match format {
+        UnitTestReportFormat::Json => {
+            write_unit_test_report_json(unit_test_results, filter_out_names, writer)
+        }
+        UnitTestReportFormat::JUnit => {
+            write_unit_test_report_junit(unit_test_results, filter_out_names, writer)
+        }
+    }
+}
+
+fn write_unit_test_report_json(
+    unit_test_results: &[UnitTestResult],
+    filter_out_names: &[String],
+    writer: &mut dyn Write,
+) -> std::io::Result<()> {
+    #[derive(Serialize)]
+    struct UnitTestReport<'a> {
+        passed: usize,
+        failed: usize,
+        skipped: usize,
+        tests: Vec<UnitTestReportItem<'a>>,
+    }
+
+    #[derive(Serialize)]
+    struct UnitTestReportItem<'a> {
+        name: &'a str,
+        submodule: &'a str,
+        status: &'static str,
+        exit_code: Option<u32>,
+
+        /// in seconds
+        duration: f64,
+        message: Option<String>,
+        output: &'a str,
+    }
+
+    let passed_count = unit_test_results
+        .iter()
+        .filter(|item| item.success())
+        .count();
+
+    let mut items = vec![];
+
+    for unit_test_result in unit_test_results {
+        let expectation = unit_test_result.expectation();
+        let (status, message_opt) = match &unit_test_result.status {
+            UnitTestStatus::Error(message) => (
+                if unit_test_result.success() {
+                    "passed"
+                } else {
+                    "error"
+                },
+                Some(message.to_owned()),
+            ),
+            _ if unit_test_result.success() => ("passed", None),
+            UnitTestStatus::Exited(_) => (
+                "failed",
+                if expectation == UnitTestExpectation::Success {
+                    None
+                } else {
+                    Some(format!("expected {}", expectation))
+                },
+            ),
+            UnitTestStatus::TimedOut => ("timed_out", None),
+        };
+
+        items.push(UnitTestReportItem {
+            name: &unit_test_result.name,
+            submodule: get_unit_test_submodule_name_path(&unit_test_result.name),
+            status,
+            exit_code: unit_test_result.exit_code(),
+            duration: unit_test_result.duration.as_secs_f64(),
+            message: message_opt,
+            output: &unit_test_result.output,
+        });
+    }
+
+    for filter_out_name in filter_out_names {
+        items.push(UnitTestReportItem {
+            name: filter_out_name,
+            submodule: get_unit_test_submodule_name_path(filter_out_name),
+            status: "skipped",
+            exit_code: None,
+            duration: 0.0,
+            message: None,
+            output: "",
+        });
+    }
+
+    write_json(
+        &UnitTestReport {
+            passed: passed_count,
+            failed: unit_test_results.len() - passed_count,
+            skipped: filter_out_names.len(),
+            tests: items,
+        },
+        writer,
+    )
+}
+
+/// Write the value as JSON which is indented with 4 spaces, followed by a new line.
+fn write_json<T>(value: &T, writer: &mut dyn Write) -> std::io::Result<()>
+where
+    T: Serialize,
+{
+    let formatter = serde_json::ser::PrettyFormatter::with_indent(b"    ");
+    let mut serializer = serde_json::Serializer::with_formatter(&mut *writer, formatter);
+    value.serialize(&mut serializer)?;
+    writeln!(writer)
+}
+
+fn write_unit_test_report_junit(
+    unit_test_results: &[UnitTestResult],
+    filter_out_names: &[String],
+    writer: &mut dyn Write,
+) -> std::io::Result<()> {
+    struct TestSuite<'a> {
+        name: &'a str,
+        results: Vec<&'a UnitTestResult>,
+        skipped_names: Vec<&'a str>,
+    }
+
+    impl TestSuite<'_> {
+        fn failures(&self) -> usize {
+            self.results
+                .iter()
+                .filter(|item| !matches!(item.status, UnitTestStatus::Error(_)) && !item.success())
+                .count()
+        }
+
+        fn errors(&self) -> usize {
+            self.results
+                .iter()
+                .filter(|item| matches!(item.status, UnitTestStatus::Error(_)) && !item.success())
+                .count()
+        }
+
+        fn time(&self) -> f64 {
+            self.results
+                .iter()
+                .map(|item| item.duration.as_secs_f64())
+                .sum()
+        }
+    }
+
+    fn get_test_suite_position<'a>(test_suites: &mut Vec<TestSuite<'a>>, name: &'a str) -> usize {
+        let submodule_name_path = get_unit_test_submodule_name_path(name);
+        match test_suites
+            .iter()
+            .position(|item| item.name == submodule_name_path)
+        {
+            Some(pos) => pos,
+            None => {
+                test_suites.push(TestSuite {
+                    name: submodule_name_path,
+                    results: vec![],
+                    skipped_names: vec![],
+                });
+                test_suites.len() - 1
+            }
+        }
+    }
+
+    // group unit tests by submodule, keeps the order of the first appearance.
+    let mut test_suites: Vec<TestSuite> = vec![];
+
+    for unit_test_result in unit_test_results {
+        let pos = get_test_suite_position(&mut test_suites, &unit_test_result.name);
+        test_suites[pos].results.push(unit_test_result);
+    }
+
+    for filter_out_name in filter_out_names {
+        let pos = get_test_suite_position(&mut test_suites, filter_out_name);
+        test_suites[pos].skipped_names.push(filter_out_name);
+    }
+
+    writeln!(writer, "<?xml version=\"1.0\" encoding=\"UTF-8\"?>")?;
+    writeln!(
+        writer,
+        "<testsuites tests=\"{}\" failures=\"{}\" errors=\"{}\" skipped=\"{}\" time=\"{:.3}\">",
+        unit_test_results.len() + filter_out_names.len(),
+        test_suites
+            .iter()
+            .map(|item| item.failures())
+            .sum::<usize>(),
+        test_suites.iter().map(|item| item.errors()).sum::<usize>(),
+        filter_out_names.len(),
+        test_suites.iter().map(|item| item.time()).sum::<f64>()
+    )?;
+
+    for test_suite in &test_suites {
+        let suite_name = escape_xml_string(test_suite.name);
+
+        writeln!(
+            writer,
+            "    <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" errors=\"{}\" skipped=\"{}\" time=\"{:.3}\">",
+            suite_name,
+            test_suite.results.len() + test_suite.skipped_names.len(),
+            test_suite.failures(),
+            test_suite.errors(),
+            test_suite.skipped_names.len(),
+            test_suite.time()
+        )?;
+
+        for unit_test_result in &test_suite.results {
+            let case_name = escape_xml_string(get_unit_test_function_name(&unit_test_result.name));
+            let case_open = format!(
+                "        <testcase name=\"{}\" classname=\"{}\" time=\"{:.3}\"",
+                case_name,
+                suite_name,
+                unit_test_result.duration.as_secs_f64()
+            );
+
+            if unit_test_result.success() && unit_test_result.output.is_empty() {
+                writeln!(writer, "{}/>", case_open)?;
+                continue;
+            }
+
+            writeln!(writer, "{}>", case_open)?;
+
+            let expectation = unit_test_result.expectation();
+            match &unit_test_result.status {
+                _ if unit_test_result.success() => {
+                    // passed
+                }
+                UnitTestStatus::Exited(exit_code) => {
+                    if expectation == UnitTestExpectation::Success {
+                        writeln!(
+                            writer,
+                            "            <failure message=\"exit code {}\"/>",
+                            exit_code
+                        )?;
+                    } else {
+                        writeln!(
+                            writer,
+                            "            <failure message=\"exit code {}, expected {}\"/>",
+                            exit_code, expectation
+                        )?;
+                    }
+                }
+                UnitTestStatus::Error(message) => {
+                    writeln!(
+                        writer,
+                        "            <error message=\"{}\"/>",
+                        escape_xml_string(message)
+                    )?;
+                }
+                UnitTestStatus::TimedOut => {
+                    writeln!(writer, "            <failure message=\"timed out\"/>")?;
+                }
+            }
+
+            if !unit_test_result.output.is_empty() {
+                writeln!(
+                    writer,
+                    "            <system-out>{}</system-out>",
+                    escape_xml_string(&unit_test_result.output)
+                )?;
+            }
+
+            writeln!(writer, "        </testcase>")?;
         }
+
+        for skipped_name in &test_suite.skipped_names {
+            writeln!(
+                writer,
+                "        <testcase name=\"{}\" classname=\"{}\" time=\"0.000\">",
+                escape_xml_string(get_unit_test_function_name(skipped_name)),
+                suite_name
+            )?;
+            writeln!(writer, "            <skipped/>")?;
+            writeln!(writer, "        </testcase>")?;
+        }
+
+        writeln!(writer, "    </testsuite>")?;
+    }
+
+    writeln!(writer, "</testsuites>")
+}
+
+/// e.g. "network::http::test_get" -> "network::http"
+fn get_unit_test_submodule_name_path(name: &str) -> &str {
+    match name.rsplit_once(NAME_PATH_SEPARATOR) {
+        Some((submodule_name_path, _)) => submodule_name_path,
+        None => "",
+    }
+}
+
+/// e.g. "network::http::test_get" -> "test_get"
+fn get_unit_test_function_name(name: &str) -> &str {
+    match name.rsplit_once(NAME_PATH_SEPARATOR) {
+        Some((_, function_name)) => function_name,
+        None => name,
+    }
+}
+
+fn escape_xml_string(text: &str) -> String {
+    let mut ss = String::new();
+    for c in text.chars() {
+        match c {
+            '&' => ss.push_str("&amp;"),
+            '<' => ss.push_str("&lt;"),
+            '>' => ss.push_str("&gt;"),
+            '"' => ss.push_str("&quot;"),
+            '\'' => ss.push_str("&apos;"),
+            '\n' => ss.push_str("&#10;"),
+            c if (c as u32) < 0x20 && c != '\t' && c != '\r' => {
+                // control characters are not allowed in XML 1.0
+            }
+            _ => ss.push(c),
+        }
+    }
+    ss
+}
+
+pub fn launch_single_file_application(
+    script_file_path: &Path,
+    arguments: Vec<String>,                // program arguments
+    environments: HashMap<String, String>, // environment variables
+    logger: &mut dyn Write,
+) -> Result<u32, GenericError> {
+    launch_single_file_application_with_limits(
+        script_file_path,
+        "",
+        arguments,
+        environments,
+        &RuntimeLimits::default(),
+        true,
+        logger,
+    )
+}
+
+/// The same as `launch_single_file_application`, but with the specified limits,
+/// the limits that are not specified are taken from the inline configuration.
+///
+/// The image of the application is loaded from the cache in the folder
+/// `{runtime_home}/cache` if `use_cache` is true, otherwise the application
+/// is always rebuilt (and the cache is left untouched).
+///
+/// executable_unit_name:
+///
+/// - "" (empty string): runs the function `_start`.
+/// - ":{unit_name}": runs the function `_start_{unit_name}`.
+pub fn launch_single_file_application_with_limits(
+    script_file_path: &Path,
+    executable_unit_name: &str,
+    arguments: Vec<String>,                // program arguments
+    environments: HashMap<String, String>, // environment variables
+    runtime_limits: &RuntimeLimits,
+    use_cache: bool,
+    logger: &mut dyn Write,
+) -> Result<u32, GenericError> {
+    let runtime_property = RuntimeProperty::from_runtime_exec_file()?;
+
+    let runtime_home = &runtime_property.runtime_home;
+    if !runtime_home.exists() {
+        std::fs::create_dir_all(runtime_home).unwrap();
+    }
+
+    let (main_image_data, image_file_paths, entry_point_entries) =
+        load_single_file_application(script_file_path, &runtime_property, use_cache, logger)?;
+
+    let entry_point_name = get_entry_point_name_by_executable_unit_name(executable_unit_name)?;
+    if !executable_unit_name.is_empty()
+        && !entry_point_entries
+            .iter()
+            .any(|entry_point_entry| entry_point_entry.unit_name == entry_point_name)
+    {
+        return Err(Box::new(RuntimeError::Message(format!(
+            "The executable unit \"{}\" does not exist, the function \"{}{}\" is required.",
+            executable_unit_name, EXECUTABLE_UNIT_FUNCTION_NAME_PREFIX, entry_point_name
+        ))));
+    }
+
+    let source_code = std::fs::read_to_string(script_file_path)?;
+    let runtime_limits = match load_inline_config_from_single_file_application_source(&source_code)?
+    {
+        Some(module_config) => runtime_limits.merge_config(&module_config.runtime),
+        None => runtime_limits.clone(),
+    };
+
+    // create process
+
+    let program = Program {
+        main_image_data_opt: Some(main_image_data),
+        image_file_paths,
+        application_path: script_file_path.to_path_buf(),
+        is_script: true,
+        arguments,
+        environments,
+    };
+
+    execute_with_limits(program, &entry_point_name, &runtime_limits, logger)
+}
+
+/// A unit test or an integration test to run.
+struct UnitTestItem {
+    /// The name of the test, it is the same as the entry point name for unit tests.
+    name: String,
+    entry_point_name: String,
+
+    /// The error message if the test fails to build.
+    program_result: Result<Arc<Program>, String>,
+}
+
+/// Run at most `options.threads` unit tests at the same time.
+///
+/// Each unit test runs in its own child process (see `ProgramProcess`)
+/// and gets its own process context, so that the state of memory and data
+/// is not leaked between unit tests, and the output of each unit test is
+/// captured separately.
+///
+/// A unit test that exceeds `options.timeout` is killed and marked as `TimedOut`.
+///
+/// Returns the results in the same order as `unit_tests`.
+fn execute_unit_tests(
+    unit_tests: Vec<UnitTestItem>,
+    options: &UnitTestOptions,
+    logger: &mut dyn Write,
+) -> Result<Vec<UnitTestResult>, GenericError> {
+    let max_running = options.threads.max(1);
+
+    let mut statuses: Vec<Option<(UnitTestStatus, Duration, /* output */ String)>> =
+        vec![None; unit_tests.len()];
+
+    // `(index, process)`
+    let mut running_items: Vec<(usize, ProgramProcess)> = vec![];
+    let mut next_index = 0;
+
+    while next_index < unit_tests.len() || !running_items.is_empty() {
+        let mut finished_items = vec![];
+
+        // start the pending unit tests
+        while next_index < unit_tests.len() && running_items.len() < max_running {
+            let unit_test = &unit_tests[next_index];
+            let process_result = match &unit_test.program_result {
+                Ok(program) => ProgramProcess::spawn(
+                    program,
+                    &unit_test.entry_point_name,
+                    None,
+                    options.capture_output,
+                )
+                .map_err(|e| format!("Failed to start the test process: {}", e)),
+                Err(message) => Err(message.clone()),
+            };
+
+            match process_result {
+                Ok(process) => running_items.push((next_index, process)),
+                Err(message) => finished_items.push((
+                    next_index,
+                    UnitTestStatus::Error(message),
+                    Duration::ZERO,
+                    String::new(),
+                )),
+            }
+
+            next_index += 1;
+        }
+
+        for idx in (0..running_items.len()).rev() {
+            let process = &mut running_items[idx].1;
+            let status_opt = match process.try_wait()? {
+                Some(Ok(exit_code)) => Some(UnitTestStatus::Exited(exit_code)),
+                Some(Err(message)) => Some(UnitTestStatus::Error(message)),
+                None => match options.timeout {
+                    Some(timeout) if process.elapsed() >= timeout => {
+                        process.kill();
+                        Some(UnitTestStatus::TimedOut)
+                    }
+                    _ => None,
+                },
+            };
+
+            if let Some(status) = status_opt {
+                let (index, mut process) = running_items.remove(idx);
+                let duration = process.elapsed();
+                let output = process.take_output()?;
+                finished_items.push((index, status, duration, output));
+            }
+        }
+
+        if finished_items.is_empty() {
+            std::thread::sleep(PROGRAM_PROCESS_POLL_INTERVAL);
+            continue;
+        }
+
+        finished_items.sort_by_key(|(index, ..)| *index);
+
+        for (index, status, duration, output) in finished_items {
+            let name = &unit_tests[index].name;
+            let passed = UnitTestExpectation::from_unit_test_name(name).is_satisfied_by(&status);
+
+            writeln!(
+                logger,
+                "Test \"{name}\": {} ({:.3}s)",
+                match status {
+                    _ if passed => "ok",
+                    UnitTestStatus::TimedOut => "TIMED OUT",
+                    _ => "FAILED",
+                },
+                duration.as_secs_f64()
+            )?;
+
+            if let UnitTestStatus::Error(message) = &status {
+                writeln!(logger, "    {}", message)?;
+            }
+
+            if !passed && !output.is_empty() {
+                writeln!(logger, "---- output of \"{name}\" ----")?;
+                write!(logger, "{}", output)?;
+                if !output.ends_with('\n') {
+                    writeln!(logger)?;
+                }
+                writeln!(logger, "----")?;
+            }
+
+            statuses[index] = Some((status, duration, output));
+        }
+    }
+
+    let unit_test_results = unit_tests
+        .into_iter()
+        .zip(statuses)
+        .map(|(unit_test, status_opt)| {
+            let (status, duration, output) = status_opt.unwrap();
+            UnitTestResult::new(unit_test.name, status, duration, output)
+        })
+        .collect::<Vec<_>>();
+
+    Ok(unit_test_results)
+}
+
+/// internal entry point names:
+///
+/// - internal entry point name: "_start"
+///   executes function: '{app_module_name}::_start' (the default entry point)
+///   user CLI unit name: "" (empty string)
+///
+/// - internal entry point name: "{submodule_name}"
+///   executes function: '{app_module_name}::app::{submodule_name}::_start' (the additional executable units)
+///   user CLI unit name: ":{submodule_name}"
+///
+/// - internal entry point name: "{submodule_name}::test_*"
+///   executes function: '{app_module_name}::tests::{submodule_name}::test_*' (unit tests)
+///   user CLI unit name: name path prefix, e.g. "{submodule_name}", "{submodule_name}::test_get_"
+///
+/// Run the program in a child process (see `ProgramProcess`) if there is any
+/// limit, and kill the child process when a limit is exceeded, the program
+/// runs in the current process otherwise.
+fn execute_with_limits(
+    program: Program,
+    internal_entry_point_name: &str,
+    runtime_limits: &RuntimeLimits,
+    logger: &mut dyn Write,
+) -> Result<u32, GenericError> {
+    if runtime_limits.max_instructions.is_some() {
+        return Err(Box::new(RuntimeError::Message(
+            "The limit of instructions is not supported by the current processor yet.".to_owned(),
+        )));
+    }
+
+    if runtime_limits.is_unlimited() {
+        return program.run(internal_entry_point_name);
+    }
+
+    let mut process = ProgramProcess::spawn(
+        &program,
+        internal_entry_point_name,
+        runtime_limits.max_memory,
+        false,
+    )?;
+
+    loop {
+        if let Some(result) = process.try_wait()? {
+            if process.memory_limit_exceeded {
+                writeln!(
+                    logger,
+                    "The application exceeded the memory limit of {} bytes.",
+                    runtime_limits.max_memory.unwrap_or_default()
+                )?;
+            }
+
+            return result
+                .map_err(|message| -> GenericError { Box::new(RuntimeError::Message(message)) });
+        }
+
+        let exceeded_limit_opt = if let Some(timeout) = runtime_limits
+            .timeout
+            .filter(|timeout| process.elapsed() >= *timeout)
+        {
+            Some((
+                EXIT_CODE_TIME_LIMIT_EXCEEDED,
+                format!("the time limit of {:.3}s", timeout.as_secs_f64()),
+            ))
+        } else if let Some(threads) = process.get_threads() {
+            runtime_limits
+                .max_threads
+                .filter(|max| threads > *max)
+                .map(|max_threads| {
+                    (
+                        EXIT_CODE_THREADS_LIMIT_EXCEEDED,
+                        format!("the threads limit of {}", max_threads),
+                    )
+                })
+        } else {
+            None
+        };
+
+        if let Some((exit_code, limit_description)) = exceeded_limit_opt {
+            process.kill();
+            writeln!(logger, "The application exceeded {}.", limit_description)?;
+            return Ok(exit_code);
+        }
+
+        std::thread::sleep(PROGRAM_PROCESS_POLL_INTERVAL);
+    }
+}
+
+/// The environment variable that controls the details of the error report
+/// when a program aborts (e.g. the VM traps or panics):
+///
+/// - unset or "0": the error message and the entry point name.
+/// - any other value (e.g. "1"): also the details of the error, i.e. the
+///   debug representation which includes the fields of the error, and the
+///   backtrace of the runtime when the VM panics.
+pub const ENVIRONMENT_VARIABLE_NAME_BACKTRACE: &str = "ANC_BACKTRACE";
+
+thread_local! {
+    /// The backtrace of the latest panic of the current thread, it is captured
+    /// by the panic hook installed by `install_backtrace_panic_hook`.
+    static PANIC_BACKTRACE: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+fn is_backtrace_enabled() -> bool {
+    std::env::var(ENVIRONMENT_VARIABLE_NAME_BACKTRACE)
+        .map(|value| !value.is_empty() && value != "0")
+        .unwrap_or(false)
+}
+
+/// Install a panic hook which captures the backtrace of the panicking thread
+/// (the stack is already unwound when the panic is caught), so that the error
+/// report of a panicked VM includes the backtrace of the runtime. It does
+/// nothing unless the environment variable `ENVIRONMENT_VARIABLE_NAME_BACKTRACE`
+/// is set.
+///
+/// The panic hook is global to the process, so it is installed by the
+/// executable (i.e. `ancrt`) rather than the runtime. The previous hook is
+/// kept, so the panic message is still printed.
+pub fn install_backtrace_panic_hook() {
+    if !is_backtrace_enabled() {
+        return;
+    }
+
+    let previous_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |panic_info| {
+        let backtrace = Backtrace::force_capture().to_string();
+        let _ = PANIC_BACKTRACE.try_with(|slot| *slot.borrow_mut() = Some(backtrace));
+        previous_hook(panic_info);
+    }));
+}
+
+fn get_panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(text) = payload.downcast_ref::<&str>() {
+        text.to_string()
+    } else if let Some(text) = payload.downcast_ref::<String>() {
+        text.to_owned()
+    } else {
+        "unknown reason".to_owned()
+    }
+}
+
+/// Start the program, and turn the errors and panics of the
+/// VM into readable error messages.
+///
+/// todo:: the call frames of the program, see "Backtrace of programs"
+/// in `docs/design_notes.md`.
+fn start_program_with_error_report(
+    process_context: &ProcessContext,
+    internal_entry_point_name: &str,
+) -> Result<u32, GenericError> {
+    let show_details = is_backtrace_enabled();
+
+    let result = std::panic::catch_unwind(AssertUnwindSafe(|| {
+        start_program(process_context, internal_entry_point_name, vec![])
+    }));
+
+    let message = match result {
+        Ok(Ok(exit_code)) => {
+            return Ok(exit_code);
+        }
+        Ok(Err(e)) => {
+            if show_details {
+                format!(
+                    "Program aborted at entry point \"{}\": {}\nDetails: {:?}",
+                    internal_entry_point_name, e, e
+                )
+            } else {
+                format!(
+                    "Program aborted at entry point \"{}\": {}\nRun with environment variable `{}=1` to show the details.",
+                    internal_entry_point_name, e, ENVIRONMENT_VARIABLE_NAME_BACKTRACE
+                )
+            }
+        }
+        Err(payload) => {
+            let panic_message = get_panic_message(payload.as_ref());
+
+            // the backtrace is absent if the panic hook is not installed,
+            // or the VM panicked in another thread.
+            let backtrace_opt = if show_details {
+                PANIC_BACKTRACE.with(|slot| slot.borrow_mut().take())
+            } else {
+                None
+            };
+
+            match backtrace_opt {
+                Some(backtrace) => format!(
+                    "The VM panicked at entry point \"{}\": {}\nBacktrace of the runtime:\n{}",
+                    internal_entry_point_name, panic_message, backtrace
+                ),
+                None if show_details => format!(
+                    "The VM panicked at entry point \"{}\": {}",
+                    internal_entry_point_name, panic_message
+                ),
+                None => format!(
+                    "The VM panicked at entry point \"{}\": {}\nRun with environment variable `{}=1` to show the backtrace.",
+                    internal_entry_point_name, panic_message, ENVIRONMENT_VARIABLE_NAME_BACKTRACE
+                ),
+            }
+        }
+    };
+
+    Err(Box::new(RuntimeError::Message(message)))
+}
+
+fn map_image_files(image_file_paths: &[PathBuf]) -> Result<Vec<Mmap>, RuntimeError> {
+    let mut mapped_files = vec![];
+
+    for image_file_path in image_file_paths {
+        let image_file =
+            File::open(image_file_path).map_err(|e| RuntimeError::Message(e.to_string()))?;
+        let mmap = unsafe { Mmap::map(&image_file).expect("Failed to map the image file.") };
+        mapped_files.push(mmap);
+    }
+
+    Ok(mapped_files)
+}
+
+fn load_application(
+    module_path: &Path,
+    runtime_property: &RuntimeProperty,
+    include_unit_tests: bool,
+    logger: &mut dyn Write,
+) -> Result<(Vec<PathBuf>, Vec<EntryPointEntry>), RuntimeError> {
+    let (_, index_entry, application_image_file_full_path) = build_application_by_dependency_tree(
+        module_path,
+        ModuleDependencyType::Local,
+        runtime_property,
+        include_unit_tests,
+        logger,
+    )?;
+
+    let mut image_file_paths = vec![application_image_file_full_path];
+
+    for dynamic_link_module_entry in &index_entry.dynamic_link_module_entries[1..] {
+        let image_file_path = get_shared_module_image_file_path_by_dynamic_link_module_entry(
+            dynamic_link_module_entry,
+            runtime_property,
+        );
+        image_file_paths.push(image_file_path);
+    }
+
+    let ImageIndexEntry {
+        entry_point_entries,
+        ..
+    } = index_entry;
+
+    Ok((image_file_paths, entry_point_entries))
+}
+
+fn load_single_file_application(
+    script_file_path: &Path,
+    runtime_property: &RuntimeProperty,
+    use_cache: bool,
+    logger: &mut dyn Write,
+) -> Result<(Vec<u8>, Vec<PathBuf>, Vec<EntryPointEntry>), RuntimeError> {
+    let (_, index_entry, main_image_data) = if use_cache {
+        load_or_build_application_by_single_file(script_file_path, runtime_property, logger)?
+    } else {
+        build_application_by_single_file(script_file_path, runtime_property, logger)?
+    };
+
+    get_dependent_image_file_paths(main_image_data, index_entry, runtime_property)
+}
+
+fn load_single_file_application_in_module(
+    script_file_path: &Path,
+    module_path: &Path,
+    module_name: &str,
+    include_benchmarks: bool,
+    runtime_property: &RuntimeProperty,
+    logger: &mut dyn Write,
+) -> Result<(Vec<u8>, Vec<PathBuf>, Vec<EntryPointEntry>), RuntimeError> {
+    let (_, index_entry, main_image_data) = build_application_by_single_file_in_module(
+        script_file_path,
+        module_path,
+        module_name,
+        include_benchmarks,
+        runtime_property,
+        logger,
+    )?;
+
+    get_dependent_image_file_paths(main_image_data, index_entry, runtime_property)
+}
+
+/// Get the image file paths of the dependent modules of an in-memory application image.
+fn get_dependent_image_file_paths(
+    main_image_data: Vec<u8>,
+    index_entry: ImageIndexEntry,
+    runtime_property: &RuntimeProperty,
+) -> Result<(Vec<u8>, Vec<PathBuf>, Vec<EntryPointEntry>), RuntimeError> {
+    let mut image_file_paths = vec![];
+
+    for dynamic_link_module_entry in &index_entry.dynamic_link_module_entries[1..] {
+        let image_file_path = get_shared_module_image_file_path_by_dynamic_link_module_entry(
+            dynamic_link_module_entry,
+            runtime_property,
+        );
+        image_file_paths.push(image_file_path);
+    }
+
+    let ImageIndexEntry {
+        entry_point_entries,
+        ..
+    } = index_entry;
+
+    Ok((main_image_data, image_file_paths, entry_point_entries))
+}
+
+pub struct MappedFileProcessResource {
+    mapped_files: Vec<Mmap>,
+    process_property: ProcessProperty,
+    external_function_table: Mutex<ExternalFunctionTable>,
+}
+
+impl MappedFileProcessResource {
+    pub fn new(mapped_files: Vec<Mmap>, process_property: ProcessProperty) -> Self {
+        Self {
+            mapped_files,
+            process_property,
+            external_function_table: Mutex::new(ExternalFunctionTable::default()),
+        }
+    }
+}
+
+impl ProcessResource for MappedFileProcessResource {
+    fn create_process_context(&self) -> Result<ProcessContext, ImageError> {
+        let mut module_images = vec![];
+
+        for file in &self.mapped_files {
+            let module_image = ModuleImage::read(file)?;
+            module_images.push(module_image);
+        }
+
+        let process_context = ProcessContext::new(
+            &self.process_property,
+            &self.external_function_table,
+            module_images,
+        );
+
+        Ok(process_context)
+    }
+}
+
+/// The resource of an application whose main image is in memory, the images
+/// of the shared modules are either the mapped image files (by default) or
+/// the image data in memory (i.e. `ScriptFileProcessResource<Vec<u8>>`).
+pub struct ScriptFileProcessResource<T = Mmap>
+where
+    T: Deref<Target = [u8]>,
+{
+    main_image_data: Vec<u8>,
+    mapped_files: Vec<T>,
+    process_property: ProcessProperty,
+    external_function_table: Mutex<ExternalFunctionTable>,
+}
+
+impl<T> ScriptFileProcessResource<T>
+where
+    T: Deref<Target = [u8]>,
+{
+    pub fn new(
+        main_image_data: Vec<u8>,
+        mapped_files: Vec<T>,
+        process_property: ProcessProperty,
+    ) -> Self {
+        Self {
+            main_image_data,
+            mapped_files,
+            process_property,
+            external_function_table: Mutex::new(ExternalFunctionTable::default()),
+        }
+    }
+}
+
+impl<T> ProcessResource for ScriptFileProcessResource<T>
+where
+    T: Deref<Target = [u8]>,
+{
+    fn create_process_context(&self) -> Result<ProcessContext, ImageError> {
+        let mut module_images = vec![];
+
+        let main_module_image = ModuleImage::read(&self.main_image_data)?;
+        module_images.push(main_module_image);
+
+        for file in &self.mapped_files {
+            let module_image = ModuleImage::read(file)?;
+            module_images.push(module_image);
+        }
+
+        let process_context = ProcessContext::new(
+            &self.process_property,
+            &self.external_function_table,
+            module_images,
+        );
+
+        Ok(process_context)
+    }
+}
+
+/// Run an application from the images in memory, it is used for
+/// embedding the runtime in Rust programs, e.g.
+///
+/// ```text
+/// let exit_code = EmbeddedApplicationBuilder::new(application_image_data)
+///     .shared_module(module_image_data)
+///     .arguments(vec!["--verbose".to_owned()])
+///     .run("_start")?;
+/// ```
+///
+/// The shared modules must be added in the same order as the dynamic link
+/// module entries of the application image (excluding the first one, which is
+/// the application itself).
+///
+/// todo:: host functions, see "Host functions" in `docs/design_notes.md`.
+pub struct EmbeddedApplicationBuilder {
+    application_image_data: Vec<u8>,
+    shared_module_image_datas: Vec<Vec<u8>>,
+    application_path: PathBuf,
+    arguments: Vec<String>,
+    environments: HashMap<String, String>,
+}
+
+impl EmbeddedApplicationBuilder {
+    pub fn new(application_image_data: Vec<u8>) -> Self {
+        Self {
+            application_image_data,
+            shared_module_image_datas: vec![],
+            application_path: PathBuf::new(),
+            arguments: vec![],
+            environments: HashMap::new(),
+        }
+    }
+
+    /// Create from the entries of an application image, e.g. the
+    /// image built by `build_application_by_single_file`.
+    pub fn from_entries(
+        common_entry: &ImageCommonEntry,
+        index_entry: &ImageIndexEntry,
+    ) -> Result<Self, RuntimeError> {
+        let mut application_image_data: Vec<u8> = vec![];
+
+        write_image_file(common_entry, index_entry, &mut application_image_data)
+            .map_err(|e| RuntimeError::Message(format!("{}", e)))?;
+
+        Ok(Self::new(application_image_data))
+    }
+
+    pub fn shared_module(mut self, shared_module_image_data: Vec<u8>) -> Self {
+        self.shared_module_image_datas
+            .push(shared_module_image_data);
+        self
+    }
+
+    /// The path that the program gets as the application path,
+    /// it is empty by default.
+    pub fn application_path(mut self, application_path: &Path) -> Self {
+        self.application_path = application_path.to_path_buf();
+        self
+    }
+
+    pub fn arguments(mut self, arguments: Vec<String>) -> Self {
+        self.arguments = arguments;
+        self
+    }
+
+    pub fn environments(mut self, environments: HashMap<String, String>) -> Self {
+        self.environments = environments;
+        self
+    }
+
+    pub fn build(self) -> ScriptFileProcessResource<Vec<u8>> {
+        let process_property = ProcessProperty {
+            application_path: self.application_path,
+            is_script: true,
+            arguments: self.arguments,
+            environments: self.environments,
+        };
+
+        ScriptFileProcessResource::new(
+            self.application_image_data,
+            self.shared_module_image_datas,
+            process_property,
+        )
+    }
+
+    /// Run the specified entry point, e.g. "_start" for the default
+    /// entry point, see `execute_with_limits` for the entry point names.
+    pub fn run(self, internal_entry_point_name: &str) -> Result<u32, GenericError> {
+        let resource = self.build();
+        let process_context = resource.create_process_context()?;
+        start_program_with_error_report(&process_context, internal_entry_point_name)
+    }
+}
+
+/// A loaded application for calling entry points repeatedly, e.g. the
+/// plugins of a host program.
+///
+/// The application is built (if necessary) and its image files are mapped only
+/// once, and the external libraries loaded by the external function table are
+/// shared by all calls. Each call gets a new process context, so the state of
+/// memory and data is not kept between calls.
+///
+/// todo:: calling functions with typed arguments and results, see
+/// "Calling functions of an application" in `docs/design_notes.md`.
+pub struct Application {
+    resource: MappedFileProcessResource,
+    entry_point_names: Vec<String>,
+}
+
+impl Application {
+    pub fn load(
+        module_path: &Path,
+        environments: HashMap<String, String>, // environment variables
+        logger: &mut dyn Write,
+    ) -> Result<Self, GenericError> {
+        let runtime_property = RuntimeProperty::from_runtime_exec_file()?;
+
+        let runtime_home = &runtime_property.runtime_home;
+        if !runtime_home.exists() {
+            std::fs::create_dir_all(runtime_home).unwrap();
+        }
+
+        let (image_file_paths, entry_point_entries) =
+            load_application(module_path, &runtime_property, false, logger)?;
+
+        let entry_point_names = entry_point_entries
+            .into_iter()
+            .map(|entry_point_entry| entry_point_entry.unit_name)
+            .collect::<Vec<_>>();
+
+        let process_property = ProcessProperty {
+            application_path: module_path.to_path_buf(),
+            is_script: false,
+            arguments: vec![],
+            environments,
+        };
+
+        let resource =
+            MappedFileProcessResource::new(map_image_files(&image_file_paths)?, process_property);
+
+        Ok(Self {
+            resource,
+            entry_point_names,
+        })
+    }
+
+    /// The internal entry point names, e.g. "_start" and the names of
+    /// the additional executable units.
+    pub fn entry_point_names(&self) -> &[String] {
+        &self.entry_point_names
+    }
+
+    /// Call the specified entry point with the program arguments,
+    /// returns the exit code.
+    pub fn call(
+        &mut self,
+        internal_entry_point_name: &str,
+        arguments: Vec<String>,
+    ) -> Result<u32, GenericError> {
+        if !self
+            .entry_point_names
+            .iter()
+            .any(|name| name == internal_entry_point_name)
+        {
+            return Err(Box::new(RuntimeError::Message(format!(
+                "Entry point \"{}\" not found.",
+                internal_entry_point_name
+            ))));
+        }
+
+        self.resource.process_property.arguments = arguments;
+
+        let process_context = self.resource.create_process_context()?;
+        start_program_with_error_report(&process_context, internal_entry_point_name)
+    }
+}
+
+/// The module name of the input of the REPL.
+pub const REPL_MODULE_NAME: &str = "repl";
+
+/// The inputs of the REPL that begin with these keywords are
+/// definitions, the other inputs are expressions.
+const REPL_DEFINITION_KEYWORDS: [&str; 7] = [
+    "fn", "pub", "data", "readonly", "uninit", "import", "external",
+];
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum ReplOutput {
+    /// The definition is accepted.
+    Defined,
+
+    /// The value of the expression.
+    Value(u32),
+}
+
+/// An interactive session that accumulates definitions (e.g. functions,
+/// data and imports) and evaluates expressions.
+///
+/// Each input is assembled together with the previous definitions as a
+/// single-file application in memory, so the builtin module `std` is
+/// available if it is installed. A definition is kept only if it is assembled
+/// and linked successfully. An expression is wrapped in the function
+/// `fn _start() -> i32 {...}` and its value is the exit code, so the type
+/// of expression must be `i32`.
+///
+/// Each expression runs in a new process context, the definitions of data
+/// persist but the changes of their values do not.
+///
+/// todo:: values of other types, see "Calling functions of an application"
+/// in the document `design_notes.md`.
+pub struct Repl {
+    runtime_property: RuntimeProperty,
+    working_path: PathBuf,
+    definitions: Vec<String>,
+}
+
+impl Repl {
+    /// The `working_path` is the folder that the paths of local
+    /// dependencies are relative to, it is usually the current directory.
+    pub fn new(working_path: &Path) -> Result<Self, GenericError> {
+        let runtime_property = RuntimeProperty::from_runtime_exec_file()?;
+
+        let runtime_home = &runtime_property.runtime_home;
+        if !runtime_home.exists() {
+            std::fs::create_dir_all(runtime_home).unwrap();
+        }
+
+        Ok(Self {
+            runtime_property,
+            working_path: working_path.to_path_buf(),
+            definitions: vec![],
+        })
+    }
+
+    /// The accepted definitions in the order of input.
+    pub fn definitions(&self) -> &[String] {
+        &self.definitions
+    }
+
+    /// Remove all definitions.
+    pub fn reset(&mut self) {
+        self.definitions.clear();
+    }
+
+    pub fn eval(
+        &mut self,
+        input: &str,
+        logger: &mut dyn Write,
+    ) -> Result<ReplOutput, GenericError> {
+        let input = input.trim();
+        let definitions_source_code = self.definitions.join("\n");
+
+        if is_repl_definition(input) {
+            let source_code = format!("{}\n{}\n", definitions_source_code, input);
+            build_application_by_source_code(
+                &source_code,
+                &self.working_path,
+                REPL_MODULE_NAME,
+                &self.runtime_property,
+                logger,
+            )?;
+
+            self.definitions.push(input.to_owned());
+            return Ok(ReplOutput::Defined);
+        }
+
+        let source_code = format!(
+            "{}\nfn {}() -> i32 {{\n{}\n}}\n",
+            definitions_source_code, DEFAULT_ENTRY_FUNCTION_NAME, input
+        );
+        let (_, index_entry, main_image_data) = build_application_by_source_code(
+            &source_code,
+            &self.working_path,
+            REPL_MODULE_NAME,
+            &self.runtime_property,
+            logger,
+        )?;
+
+        let (main_image_data, image_file_paths, _) =
+            get_dependent_image_file_paths(main_image_data, index_entry, &self.runtime_property)?;
+
+        let process_property = ProcessProperty {
+            application_path: self.working_path.clone(),
+            is_script: true,
+            arguments: vec![],
+            environments: HashMap::new(),
+        };
+
+        let mapped_files = map_image_files(&image_file_paths)?;
+        let resource =
+            ScriptFileProcessResource::new(main_image_data, mapped_files, process_property);
+        let process_context = resource.create_process_context()?;
+        let value = start_program_with_error_report(&process_context, DEFAULT_ENTRY_FUNCTION_NAME)?;
+
+        Ok(ReplOutput::Value(value))
+    }
+}
+
+fn is_repl_definition(input: &str) -> bool {
+    let first_word = input
+        .split(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+        .next()
+        .unwrap_or("");
+    REPL_DEFINITION_KEYWORDS.contains(&first_word)
+}
+
+static TEMPORARY_FILE_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/// The interval of checking the state of the programs running in child processes.
+const PROGRAM_PROCESS_POLL_INTERVAL: Duration = Duration::from_millis(5);
+
+/// The prefixes of the result which is sent from the child process to the runtime.
+const PROGRAM_PROCESS_RESULT_EXITED: char = 'X';
+const PROGRAM_PROCESS_RESULT_ERROR: char = 'E';
+
+/// The environment variable that carries the path of the request file
+/// to the child process, see `serve_program_process`.
+pub const ENVIRONMENT_VARIABLE_NAME_PROGRAM_PROCESS: &str = "ANCRT_PROGRAM_PROCESS";
+
+/// A file in the temporary folder, it is removed when it is dropped.
+struct TemporaryFile {
+    file_path: PathBuf,
+}
+
+impl TemporaryFile {
+    /// The file is opened in append mode.
+    fn create() -> std::io::Result<(Self, File)> {
+        let file_path = std::env::temp_dir().join(format!(
+            "ancrt-{}-{}",
+            std::process::id(),
+            TEMPORARY_FILE_COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+
+        let file = File::options()
+            .read(true)
+            .append(true)
+            .create_new(true)
+            .open(&file_path)?;
+
+        Ok((Self { file_path }, file))
+    }
+}
+
+impl Drop for TemporaryFile {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.file_path);
+    }
+}
+
+/// A program to run, i.e. the images of an application and the
+/// arguments and environment variables of the process.
+///
+/// It runs either in the current process (see `Program::run`), or in
+/// child processes (see `ProgramProcess`).
+struct Program {
+    /// The main image of a single-file application, which is built in
+    /// memory, it is prepended to the images in `image_file_paths`.
+    main_image_data_opt: Option<Vec<u8>>,
+    image_file_paths: Vec<PathBuf>,
+    application_path: PathBuf,
+    is_script: bool,
+    arguments: Vec<String>,
+    environments: HashMap<String, String>,
+}
+
+impl Program {
+    fn create_process_property(&self) -> ProcessProperty {
+        ProcessProperty {
+            application_path: self.application_path.clone(),
+            is_script: self.is_script,
+            arguments: self.arguments.clone(),
+            environments: self.environments.clone(),
+        }
+    }
+
+    /// Run the program in the current process.
+    fn run(self, internal_entry_point_name: &str) -> Result<u32, GenericError> {
+        let process_property = self.create_process_property();
+        let mapped_files = map_image_files(&self.image_file_paths)?;
+
+        match self.main_image_data_opt {
+            Some(main_image_data) => {
+                let resource =
+                    ScriptFileProcessResource::new(main_image_data, mapped_files, process_property);
+                let process_context = resource.create_process_context()?;
+                start_program_with_error_report(&process_context, internal_entry_point_name)
+            }
+            None => {
+                let resource = MappedFileProcessResource::new(mapped_files, process_property);
+                let process_context = resource.create_process_context()?;
+                start_program_with_error_report(&process_context, internal_entry_point_name)
+            }
+        }
+    }
+}
+
+/// The request which is sent from the runtime to the child process.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct ProgramProcessRequest {
+    /// The first one is the main (application) image.
+    image_file_paths: Vec<PathBuf>,
+    application_path: PathBuf,
+    is_script: bool,
+    arguments: Vec<String>,
+    environments: HashMap<String, String>,
+    internal_entry_point_name: String,
+
+    /// The limit of the data memory in bytes, see `RuntimeLimits::max_memory`.
+    max_memory: Option<u64>,
+
+    /// The exit code or the error message is written to this file by the
+    /// child process, see `PROGRAM_PROCESS_RESULT_*`.
+    result_file_path: PathBuf,
+}
+
+impl ProgramProcessRequest {
+    fn load(request_file_path: &Path) -> Result<Self, RuntimeError> {
+        let request_file =
+            File::open(request_file_path).map_err(|e| RuntimeError::Message(format!("{}", e)))?;
+
+        ason::from_reader(request_file).map_err(|e| RuntimeError::Message(format!("{}", e)))
+    }
+
+    fn run(&self) -> Result<u32, GenericError> {
+        // the environment variables of the program are also the ones of the child
+        // process, so that the external functions (e.g. the `getenv()` of libc) get them.
+        for (name, value) in &self.environments {
+            std::env::set_var(name, value);
+        }
+
+        let process_property = ProcessProperty {
+            application_path: self.application_path.clone(),
+            is_script: self.is_script,
+            arguments: self.arguments.clone(),
+            environments: self.environments.clone(),
+        };
+
+        let mapped_files = map_image_files(&self.image_file_paths)?;
+        let resource = MappedFileProcessResource::new(mapped_files, process_property);
+        let process_context = resource.create_process_context()?;
+        start_program_with_error_report(&process_context, &self.internal_entry_point_name)
+    }
+}
+
+/// Run the program requested by the runtime and exit, if the current process
+/// is a child process started by the runtime (i.e. the environment variable
+/// `ENVIRONMENT_VARIABLE_NAME_PROGRAM_PROCESS` is set), it returns immediately
+/// otherwise.
+///
+/// The child processes are new instances of the current executable, so the
+/// executables that run unit tests or applications with limits (i.e. `ancrt`
+/// and the programs which embed the runtime) must call this function at
+/// the beginning of `main`.
+pub fn serve_program_process() {
+    let Some(request_file_path) = std::env::var_os(ENVIRONMENT_VARIABLE_NAME_PROGRAM_PROCESS)
+    else {
+        return;
+    };
+
+    let request = match ProgramProcessRequest::load(Path::new(&request_file_path)) {
+        Ok(request) => request,
+        Err(e) => {
+            eprintln!("Failed to load the request of the program process: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    let text = match request.max_memory.map(set_memory_limit).transpose() {
+        Err(message) => format!("{}{}", PROGRAM_PROCESS_RESULT_ERROR, message),
+        Ok(_) => {
+            // the panics of the VM are reported by `start_program_with_error_report`,
+            // and this one catches the panics while creating the process context.
+            match std::panic::catch_unwind(AssertUnwindSafe(|| request.run())) {
+                Ok(Ok(exit_code)) => format!("{}{}", PROGRAM_PROCESS_RESULT_EXITED, exit_code),
+                Ok(Err(e)) => format!("{}{}", PROGRAM_PROCESS_RESULT_ERROR, e),
+                Err(payload) => format!(
+                    "{}Failed to create the process context: {}",
+                    PROGRAM_PROCESS_RESULT_ERROR,
+                    get_panic_message(payload.as_ref())
+                ),
+            }
+        }
+    };
+
+    if let Err(e) = std::fs::write(&request.result_file_path, text) {
+        eprintln!("Failed to write the result of the program process: {}", e);
+        std::process::exit(1);
+    }
+
+    // the standard streams are flushed on exit.
+    std::process::exit(0);
+}
+
+/// Whether the data memory of the current process is limited, see `ProgramAllocator`.
+static MEMORY_LIMITED: AtomicBool = AtomicBool::new(false);
+
+/// Limit the data memory of the current (child) process.
+#[cfg(unix)]
+fn set_memory_limit(max_memory: u64) -> Result<(), String> {
+    let limit = libc::rlimit {
+        rlim_cur: max_memory as libc::rlim_t,
+        rlim_max: max_memory as libc::rlim_t,
+    };
+
+    if unsafe { libc::setrlimit(libc::RLIMIT_DATA, &limit) } != 0 {
+        return Err(format!(
+            "Failed to set the memory limit: {}",
+            std::io::Error::last_os_error()
+        ));
+    }
+
+    MEMORY_LIMITED.store(true, Ordering::Relaxed);
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn set_memory_limit(_max_memory: u64) -> Result<(), String> {
+    Err("The memory limit is not supported on this platform.".to_owned())
+}
+
+/// The global allocator of the executables which run applications with
+/// the memory limit (see `RuntimeLimits::max_memory`), i.e. `ancrt` and the
+/// programs which embed the runtime.
+///
+/// It is the system allocator, except that the process exits immediately
+/// with `EXIT_CODE_MEMORY_LIMIT_EXCEEDED` instead of aborting when an
+/// allocation fails in a child process whose memory is limited.
+///
+/// ```ignore
+/// #[global_allocator]
+/// static GLOBAL: ProgramAllocator = ProgramAllocator;
+/// ```
+pub struct ProgramAllocator;
+
+impl ProgramAllocator {
+    fn check(ptr: *mut u8) -> *mut u8 {
+        #[cfg(unix)]
+        if ptr.is_null() && MEMORY_LIMITED.load(Ordering::Relaxed) {
+            // exit without running any handler, since they may allocate as well.
+            unsafe { libc::_exit(EXIT_CODE_MEMORY_LIMIT_EXCEEDED as libc::c_int) };
+        }
+
+        ptr
+    }
+}
+
+unsafe impl GlobalAlloc for ProgramAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        Self::check(System.alloc(layout))
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        Self::check(System.alloc_zeroed(layout))
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        Self::check(System.realloc(ptr, layout, new_size))
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+/// The command that starts a new instance of the current executable
+/// as a child process, see `serve_program_process`.
+fn new_program_process_command() -> std::io::Result<Command> {
+    #[allow(unused_mut)]
+    let mut command = Command::new(std::env::current_exe()?);
+
+    // the test harness serves the request in the test `program_process`.
+    #[cfg(test)]
+    command.args([
+        "--exact",
+        "runner::tests::program_process",
+        "--nocapture",
+        "--test-threads=1",
+        "--quiet",
+    ]);
+
+    Ok(command)
+}
+
+/// A program (e.g. a unit test) running in a child process of the runtime.
+///
+/// Each program has its own process, so that:
+///
+/// - the standard output and standard error of the program are redirected
+///   without affecting the runtime (or the host application which embeds the
+///   runtime) and the other programs, the output of both the VM and the
+///   external functions (e.g. the `puts()` of libc) are captured.
+/// - the program is killed when it exceeds a limit (see `RuntimeLimits`),
+///   instead of leaving a runaway thread behind.
+/// - a crash (e.g. a segmentation fault in an external function) only
+///   fails the program itself.
+///
+/// The child process is a new instance of the current executable (instead of
+/// a fork of the runtime, which may have other threads), it gets the image
+/// file paths through a request file and creates its own process context,
+/// see `serve_program_process`.
+struct ProgramProcess {
+    child: Child,
+
+    // the temporary files are removed when the process is dropped.
+    _request_file: TemporaryFile,
+    _main_image_file_opt: Option<TemporaryFile>,
+    result_file: TemporaryFile,
+
+    /// The standard output and standard error of the child process
+    /// are redirected to this file when the output is captured.
+    output_file_opt: Option<TemporaryFile>,
+
+    start_instant: Instant,
+
+    /// The child process has exited and has been reaped.
+    reaped: bool,
+
+    /// The child process exited because its memory limit was reached,
+    /// see `ProgramAllocator`.
+    memory_limit_exceeded: bool,
+}
+
+impl ProgramProcess {
+    fn spawn(
+        program: &Program,
+        internal_entry_point_name: &str,
+        max_memory: Option<u64>,
+        capture_output: bool,
+    ) -> Result<Self, GenericError> {
+        let mut image_file_paths = vec![];
+
+        // the main image in memory is passed through a file.
+        let main_image_file_opt = match &program.main_image_data_opt {
+            Some(main_image_data) => {
+                let (main_image_file, mut file) = TemporaryFile::create()?;
+                file.write_all(main_image_data)?;
+                image_file_paths.push(main_image_file.file_path.clone());
+                Some(main_image_file)
+            }
+            None => None,
+        };
+
+        image_file_paths.extend(program.image_file_paths.iter().cloned());
+
+        let (result_file, _) = TemporaryFile::create()?;
+
+        let request = ProgramProcessRequest {
+            image_file_paths,
+            application_path: program.application_path.clone(),
+            is_script: program.is_script,
+            arguments: program.arguments.clone(),
+            environments: program.environments.clone(),
+            internal_entry_point_name: internal_entry_point_name.to_owned(),
+            max_memory,
+            result_file_path: result_file.file_path.clone(),
+        };
+
+        let (request_file, mut file) = TemporaryFile::create()?;
+        ason::to_writer(&request, &mut file)
+            .map_err(|e| RuntimeError::Message(format!("{}", e)))?;
+
+        let mut command = new_program_process_command()?;
+        command.env(
+            ENVIRONMENT_VARIABLE_NAME_PROGRAM_PROCESS,
+            &request_file.file_path,
+        );
+
+        let output_file_opt = if capture_output {
+            let (output_file, file) = TemporaryFile::create()?;
+            command.stdout(Stdio::from(file.try_clone()?));
+            command.stderr(Stdio::from(file));
+
+            // the test harness prints its header before running the test `program_process`,
+            // which is removed by the test.
+            #[cfg(test)]
+            command.env(
+                tests::ENVIRONMENT_VARIABLE_NAME_PROGRAM_PROCESS_OUTPUT,
+                &output_file.file_path,
+            );
+
+            Some(output_file)
+        } else {
+            None
+        };
+
+        let child = command.spawn()?;
+
+        Ok(Self {
+            child,
+            _request_file: request_file,
+            _main_image_file_opt: main_image_file_opt,
+            result_file,
+            output_file_opt,
+            start_instant: Instant::now(),
+            reaped: false,
+            memory_limit_exceeded: false,
+        })
+    }
+
+    fn elapsed(&self) -> Duration {
+        self.start_instant.elapsed()
+    }
+
+    /// Returns the result of the program if it has finished, or `None` if it is still running.
+    fn try_wait(&mut self) -> Result<Option<Result<u32, String>>, GenericError> {
+        let Some(exit_status) = self.child.try_wait()? else {
+            return Ok(None);
+        };
+
+        self.reaped = true;
+
+        let text = std::fs::read_to_string(&self.result_file.file_path)?;
+
+        let result = if let Some(exit_code) = text
+            .strip_prefix(PROGRAM_PROCESS_RESULT_EXITED)
+            .and_then(|value| value.parse::<u32>().ok())
+        {
+            Ok(exit_code)
+        } else if let Some(message) = text.strip_prefix(PROGRAM_PROCESS_RESULT_ERROR) {
+            Err(message.to_owned())
+        } else if text.is_empty()
+            && exit_status.code() == Some(EXIT_CODE_MEMORY_LIMIT_EXCEEDED as i32)
+        {
+            self.memory_limit_exceeded = true;
+            Ok(EXIT_CODE_MEMORY_LIMIT_EXCEEDED)
+        } else {
+            // e.g. "exit status: 1" and "signal: 11 (SIGSEGV)"
+            Err(format!("The program exited unexpectedly, {}.", exit_status))
+        };
+
+        Ok(Some(result))
+    }
+
+    fn kill(&mut self) {
+        if self.reaped {
+            return;
+        }
+
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+
+        self.reaped = true;
+    }
+
+    /// Returns the number of threads of the child process, or `None` if it is
+    /// not available, e.g. the `/proc` file system is absent.
+    fn get_threads(&self) -> Option<usize> {
+        let text = std::fs::read_to_string(format!("/proc/{}/status", self.child.id())).ok()?;
+
+        // e.g. "Threads:        4"
+        text.lines()
+            .find_map(|line| line.strip_prefix("Threads:"))
+            .and_then(|value| value.trim().parse::<usize>().ok())
+    }
+
+    /// Returns the captured output, it is empty if the output is not captured.
+    fn take_output(&mut self) -> Result<String, GenericError> {
+        let Some(output_file) = &self.output_file_opt else {
+            return Ok(String::new());
+        };
+
+        let buffer = std::fs::read(&output_file.file_path)?;
+        Ok(String::from_utf8_lossy(&buffer).into_owned())
+    }
+}
+
+impl Drop for ProgramProcess {
+    fn drop(&mut self) {
+        self.kill();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::HashMap,
+        path::PathBuf,
+        time::{Duration, Instant},
+    };
+
+    use pretty_assertions::assert_eq;
+
+    use crate::builder::build_application_by_single_file;
+    use crate::entry::{BenchmarkBaseline, ModuleConfig, RuntimeLimitConfig, RuntimeProperty};
+    use crate::locations::{get_benchmark_baseline_file_path, get_module_config_file_path};
+    use crate::runner::{
+        launch_application, launch_benchmarks, launch_integration_tests,
+        launch_single_file_application, launch_single_file_application_with_limits,
+        launch_unit_tests, list_entry_points, list_executable_units,
+        merge_test_arguments_and_environments, serve_program_process, write_entry_points,
+        write_unit_test_report, Application, ApplicationEntryPoints, BenchmarkFailure,
+        BenchmarkOptions, BenchmarkResult, EmbeddedApplicationBuilder, EntryPointListFormat,
+        ProgramAllocator, Repl, ReplOutput, RuntimeLimits, UnitTestExpectation, UnitTestFilter,
+        UnitTestOptions, UnitTestReportFormat, UnitTestResult, UnitTestStatus,
+        EXIT_CODE_TIME_LIMIT_EXCEEDED,
+    };
+
+    #[global_allocator]
+    static GLOBAL: ProgramAllocator = ProgramAllocator;
+
+    /// The environment variable that carries the path of the output file
+    /// of the program process, see `program_process`.
+    pub const ENVIRONMENT_VARIABLE_NAME_PROGRAM_PROCESS_OUTPUT: &str =
+        "ANCRT_PROGRAM_PROCESS_OUTPUT";
+
+    /// The entry of the child processes started by the tests, see `ProgramProcess`.
+    ///
+    /// It does nothing when it runs as a normal test.
+    #[test]
+    fn program_process() {
+        if let Some(output_file_path) =
+            std::env::var_os(ENVIRONMENT_VARIABLE_NAME_PROGRAM_PROCESS_OUTPUT)
+        {
+            // remove the header printed by the test harness, the standard streams
+            // are opened in append mode, so the following output starts from
+            // the beginning of the file.
+            std::fs::File::options()
+                .write(true)
+                .open(output_file_path)
+                .unwrap()
+                .set_len(0)
+                .unwrap();
+        }
+
+        serve_program_process();
+    }
+
+    fn get_resources_path_buf() -> PathBuf {
+        // `std::env::current_dir()` returns the current Rust project's root folder
+        let mut pwd = std::env::current_dir().unwrap();
+        pwd.push("tests");
+        pwd.push("resources");
+        pwd
+    }
+
+    #[test]
+    fn test_launch_application() {
+        let mut output: Vec<u8> = vec![];
+        // let mut output = stdout();
+
+        // single_module_app
+        {
+            let mut moudle_path_buf = get_resources_path_buf();
+            moudle_path_buf.push("single_module_app");
+
+            let result0 = launch_application(
+                &moudle_path_buf,
+                "",
+                vec![],
+                HashMap::<String, String>::new(),
+                &mut output,
+            );
+
+            assert_eq!(result0.unwrap(), 0);
+        }
+
+        // single_module_with_multiple_executable_units
+        {
+            let mut moudle_path_buf = get_resources_path_buf();
+            moudle_path_buf.push("single_module_with_multiple_executable_units");
+
+            let result0 = launch_application(
+                &moudle_path_buf,
+                "",
+                vec![],
+                HashMap::<String, String>::new(),
+                &mut output,
+            );
+
+            assert_eq!(result0.unwrap(), 0);
+
+            let result1 = launch_application(
+                &moudle_path_buf,
+                ":foo",
+                vec![],
+                HashMap::<String, String>::new(),
+                &mut output,
+            );
+
+            assert_eq!(result1.unwrap(), 0);
+
+            let result2 = launch_application(
+                &moudle_path_buf,
+                ":bar",
+                vec![],
+                HashMap::<String, String>::new(),
+                &mut output,
+            );
+
+            assert_eq!(result2.unwrap(), 0);
+        }
+
+        // multiple_modules
+        {
+            let mut moudle_path_buf = get_resources_path_buf();
+            moudle_path_buf.push("multiple_modules");
+            moudle_path_buf.push("app");
+
+            let result0 = launch_application(
+                &moudle_path_buf,
+                "",
+                vec![],
+                HashMap::<String, String>::new(),
+                &mut output,
+            );
+
+            assert_eq!(result0.unwrap(), 0);
+        }
+    }
+
+    #[test]
+    fn test_launch_unit_tests() {
+        let mut output: Vec<u8> = vec![];
+        // let mut output = stdout();
+
+        // single_module_with_unit_tests - without specify testing name
+        {
+            let mut moudle_path_buf = get_resources_path_buf();
+            moudle_path_buf.push("single_module_with_unit_tests");
+
+            let (results, skips) = launch_unit_tests(
+                &moudle_path_buf,
+                &UnitTestFilter::default(),
+                vec![],
+                HashMap::<String, String>::new(),
+                &UnitTestOptions::default(),
+                &mut output,
+            )
+            .unwrap();
+
+            assert_eq!(
+                results
+                    .iter()
+                    .map(|item| (item.name.as_str(), item.status.clone()))
+                    .collect::<Vec<_>>(),
+                vec![
+                    ("foo::test_add", UnitTestStatus::Exited(0)),
+                    ("foo::test_subtract", UnitTestStatus::Exited(0)),
+                    ("bar::test_multiply", UnitTestStatus::Exited(0)),
+                    ("bar::test_divide", UnitTestStatus::Exited(0)),
+                ]
+            );
+
+            assert!(skips.is_empty());
+        }
+
+        // single_module_with_unit_tests - specify testing name
+        {
+            let mut moudle_path_buf = get_resources_path_buf();
+            moudle_path_buf.push("single_module_with_unit_tests");
+
+            let (results, skips) = launch_unit_tests(
+                &moudle_path_buf,
+                &UnitTestFilter {
+                    patterns: vec!["foo".to_owned()],
+                    ..UnitTestFilter::default()
+                },
+                vec![],
+                HashMap::<String, String>::new(),
+                &UnitTestOptions::default(),
+                &mut output,
+            )
+            .unwrap();
+
+            assert_eq!(
+                results
+                    .iter()
+                    .map(|item| (item.name.as_str(), item.status.clone()))
+                    .collect::<Vec<_>>(),
+                vec![
+                    ("foo::test_add", UnitTestStatus::Exited(0)),
+                    ("foo::test_subtract", UnitTestStatus::Exited(0))
+                ]
+            );
+
+            assert_eq!(
+                skips,
+                vec![
+                    "bar::test_multiply".to_owned(),
+                    "bar::test_divide".to_owned()
+                ]
+            );
+        }
+
+        // single_module_with_expected_failure_tests
+        {
+            let mut moudle_path_buf = get_resources_path_buf();
+            moudle_path_buf.push("single_module_with_expected_failure_tests");
+
+            let (results, _) = launch_unit_tests(
+                &moudle_path_buf,
+                &UnitTestFilter::default(),
+                vec![],
+                HashMap::<String, String>::new(),
+                &UnitTestOptions::default(),
+                &mut output,
+            )
+            .unwrap();
+
+            assert_eq!(
+                results
+                    .iter()
+                    .map(|item| (item.name.as_str(), item.status.clone(), item.success()))
+                    .collect::<Vec<_>>(),
+                vec![
+                    (
+                        "expect::test_should_fail_add",
+                        UnitTestStatus::Exited(1),
+                        true
+                    ),
+                    ("expect::test_exit_3_add", UnitTestStatus::Exited(3), true),
+                    (
+                        "expect::test_should_fail_unexpected_success",
+                        UnitTestStatus::Exited(0),
+                        false
+                    ),
+                    (
+                        "expect::test_exit_2_unexpected_exit_code",
+                        UnitTestStatus::Exited(3),
+                        false
+                    ),
+                ]
+            );
+        }
+    }
+
+    #[test]
+    fn test_launch_integration_tests() {
+        let mut output: Vec<u8> = vec![];
+
+        let mut moudle_path_buf = get_resources_path_buf();
+        moudle_path_buf.push("single_module_with_integration_tests");
+
+        let (results, skips) = launch_integration_tests(
+            &moudle_path_buf,
+            &UnitTestFilter::default(),
+            vec![],
+            HashMap::<String, String>::new(),
+            &UnitTestOptions::default(),
+            &mut output,
+        )
+        .unwrap();
+
+        assert_eq!(
+            results
+                .iter()
+                .map(|item| (item.name.as_str(), item.status.clone()))
+                .collect::<Vec<_>>(),
+            vec![
+                ("integration::add", UnitTestStatus::Exited(0)),
+                (
+                    "integration::arithmetic::subtract",
+                    UnitTestStatus::Exited(0)
+                ),
+            ]
+        );
+
+        assert!(skips.is_empty());
+    }
+
+    #[test]
+    fn test_launch_integration_tests_with_build_errors() {
+        let mut output: Vec<u8> = vec![];
+
+        let mut moudle_path_buf = get_resources_path_buf();
+        moudle_path_buf.push("single_module_with_integration_errors");
+
+        // the test file that fails to build does not stop the other tests.
+        let (results, _) = launch_integration_tests(
+            &moudle_path_buf,
+            &UnitTestFilter::default(),
+            vec![],
+            HashMap::<String, String>::new(),
+            &UnitTestOptions::default(),
+            &mut output,
+        )
+        .unwrap();
+
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].name, "integration::add");
+        assert_eq!(results[0].status, UnitTestStatus::Exited(0));
+        assert_eq!(results[1].name, "integration::broken");
+
+        let UnitTestStatus::Error(message) = &results[1].status else {
+            panic!("expected an error, got {:?}", results[1].status);
+        };
+        assert!(message.starts_with("Failed to build the test file:"));
+
+        let text = String::from_utf8(output).unwrap();
+        assert!(text.contains("Test \"integration::broken\": FAILED"));
+    }
+
+    #[test]
+    fn test_launch_unit_tests_with_test_config() {
+        let mut output: Vec<u8> = vec![];
+
+        let mut moudle_path_buf = get_resources_path_buf();
+        moudle_path_buf.push("single_module_with_test_config");
+
+        // the declared arguments come first, and the declared
+        // environment variables are overridden by the specified ones.
+        let module_config =
+            ModuleConfig::load(&get_module_config_file_path(&moudle_path_buf)).unwrap();
+
+        let (arguments, environments) = merge_test_arguments_and_environments(
+            module_config.test,
+            vec!["--verbose".to_owned()],
+            HashMap::from([
+                ("LOG_LEVEL".to_owned(), "trace".to_owned()),
+                ("LANG".to_owned(), "C".to_owned()),
+            ]),
+        );
+
+        assert_eq!(
+            arguments,
+            vec![
+                "--fixtures".to_owned(),
+                "./tests/fixtures".to_owned(),
+                "--verbose".to_owned()
+            ]
+        );
+
+        assert_eq!(
+            environments,
+            HashMap::from([
+                ("LOG_LEVEL".to_owned(), "trace".to_owned()),
+                ("LOG_FILE".to_owned(), "test.log".to_owned()),
+                ("LANG".to_owned(), "C".to_owned()),
+            ])
+        );
+
+        let (results, skips) = launch_unit_tests(
+            &moudle_path_buf,
+            &UnitTestFilter::default(),
+            vec!["--verbose".to_owned()],
+            HashMap::from([("LOG_LEVEL".to_owned(), "trace".to_owned())]),
+            &UnitTestOptions::default(),
+            &mut output,
+        )
+        .unwrap();
+
+        assert_eq!(
+            results
+                .iter()
+                .map(|item| (item.name.as_str(), item.status.clone()))
+                .collect::<Vec<_>>(),
+            vec![
+                ("foo::test_add", UnitTestStatus::Exited(0)),
+                ("foo::test_environments", UnitTestStatus::Exited(0)),
+            ]
+        );
+
+        assert!(skips.is_empty());
+
+        // the declared "LOG_LEVEL" is not overridden, and
+        // the test `test_environments` fails.
+        let (results, _) = launch_unit_tests(
+            &moudle_path_buf,
+            &UnitTestFilter::default(),
+            vec![],
+            HashMap::<String, String>::new(),
+            &UnitTestOptions::default(),
+            &mut output,
+        )
+        .unwrap();
+
+        assert_eq!(results[1].name, "foo::test_environments");
+        assert_eq!(results[1].status, UnitTestStatus::Exited(1));
+    }
+
+    #[test]
+    fn test_launch_unit_tests_with_errors() {
+        let mut output: Vec<u8> = vec![];
+
+        let mut moudle_path_buf = get_resources_path_buf();
+        moudle_path_buf.push("single_module_with_error_tests");
+
+        let (results, skips) = launch_unit_tests(
+            &moudle_path_buf,
+            &UnitTestFilter::default(),
+            vec![],
+            HashMap::<String, String>::new(),
+            &UnitTestOptions::default(),
+            &mut output,
+        )
+        .unwrap();
+
+        // the run continues after a unit test is aborted
+        assert_eq!(
+            results
+                .iter()
+                .map(|item| item.name.as_str())
+                .collect::<Vec<_>>(),
+            vec![
+                "foo::test_add",
+                "foo::test_divide_by_zero",
+                "foo::test_should_fail_divide_by_zero",
+                "foo::test_add_again",
+            ]
+        );
+
+        assert_eq!(results[0].status, UnitTestStatus::Exited(0));
+        assert!(matches!(results[1].status, UnitTestStatus::Error(_)));
+        assert!(matches!(results[2].status, UnitTestStatus::Error(_)));
+        assert_eq!(results[3].status, UnitTestStatus::Exited(0));
+
+        assert!(results[2].success());
+        assert!(!results[1].success());
+
+        // the errors of unit tests are reported the same as applications
+        let UnitTestStatus::Error(message) = &results[1].status else {
+            unreachable!()
+        };
+        assert!(message.contains("at entry point \"foo::test_divide_by_zero\""));
+
+        let text = String::from_utf8(output).unwrap();
+        assert!(text.contains("Test \"foo::test_divide_by_zero\": FAILED"));
+        assert!(text.contains("Test \"foo::test_should_fail_divide_by_zero\": ok"));
+
+        assert!(skips.is_empty());
+    }
+
+    #[test]
+    fn test_launch_unit_tests_with_threads_and_timeout() {
+        let mut moudle_path_buf = get_resources_path_buf();
+        moudle_path_buf.push("single_module_with_timeout_tests");
+
+        let timeout = Duration::from_millis(500);
+
+        for threads in [1, 2, 4] {
+            let mut output: Vec<u8> = vec![];
+
+            let (results, skips) = launch_unit_tests(
+                &moudle_path_buf,
+                &UnitTestFilter::default(),
+                vec![],
+                HashMap::<String, String>::new(),
+                &UnitTestOptions {
+                    threads,
+                    timeout: Some(timeout),
+                    capture_output: false,
+                },
+                &mut output,
+            )
+            .unwrap();
+
+            // the results keep the order of the unit tests, and
+            // the run continues after a unit test times out
+            assert_eq!(
+                results
+                    .iter()
+                    .map(|item| (item.name.as_str(), item.status.clone()))
+                    .collect::<Vec<_>>(),
+                vec![
+                    ("foo::test_add", UnitTestStatus::Exited(0)),
+                    ("foo::test_loop_forever", UnitTestStatus::TimedOut),
+                    ("foo::test_add_again", UnitTestStatus::Exited(0)),
+                ]
+            );
+
+            assert!(results[1].duration >= timeout);
+
+            let text = String::from_utf8(output).unwrap();
+            assert!(text.contains("Test \"foo::test_loop_forever\": TIMED OUT"));
+
+            assert!(skips.is_empty());
+        }
+    }
+
+    #[test]
+    fn test_launch_unit_tests_with_output_capture() {
+        let mut moudle_path_buf = get_resources_path_buf();
+        moudle_path_buf.push("single_module_with_output_tests");
+
+        // the output of each unit test is captured separately
+        // while the unit tests run concurrently.
+        let mut output: Vec<u8> = vec![];
+
+        let (results, _) = launch_unit_tests(
+            &moudle_path_buf,
+            &UnitTestFilter::default(),
+            vec![],
+            HashMap::<String, String>::new(),
+            &UnitTestOptions {
+                threads: 2,
+                timeout: None,
+                capture_output: true,
+            },
+            &mut output,
+        )
+        .unwrap();
+
+        assert_eq!(
+            results
+                .iter()
+                .map(|item| (
+                    item.name.as_str(),
+                    item.status.clone(),
+                    item.output.as_str()
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                (
+                    "foo::test_print_and_pass",
+                    UnitTestStatus::Exited(0),
+                    "output of test_print_and_pass\n"
+                ),
+                (
+                    "foo::test_print_and_fail",
+                    UnitTestStatus::Exited(1),
+                    "output of test_print_and_fail\n"
+                ),
+            ]
+        );
+
+        // only the output of the failed unit tests is shown
+        let text = String::from_utf8(output).unwrap();
+        assert!(text.contains("---- output of \"foo::test_print_and_fail\" ----"));
+        assert!(text.contains("output of test_print_and_fail\n"));
+        assert!(!text.contains("output of test_print_and_pass"));
+
+        // the output is not captured
+        let mut output: Vec<u8> = vec![];
+
+        let (results, _) = launch_unit_tests(
+            &moudle_path_buf,
+            &UnitTestFilter::default(),
+            vec![],
+            HashMap::<String, String>::new(),
+            &UnitTestOptions {
+                threads: 2,
+                timeout: None,
+                capture_output: false,
+            },
+            &mut output,
+        )
+        .unwrap();
+
+        assert!(results.iter().all(|item| item.output.is_empty()));
+    }
+
+    #[test]
+    fn test_launch_benchmarks() {
+        let mut output: Vec<u8> = vec![];
+
+        let mut moudle_path_buf = get_resources_path_buf();
+        moudle_path_buf.push("single_module_with_benchmarks");
+
+        let (results, failures, skips) = launch_benchmarks(
+            &moudle_path_buf,
+            &UnitTestFilter::default(),
+            vec![],
+            HashMap::<String, String>::new(),
+            &BenchmarkOptions {
+                warm_up_iterations: 1,
+                iterations: 3,
+                save_baseline: true,
+            },
+            &mut output,
+        )
+        .unwrap();
+
+        // the benchmarks after the failed one still run
+        assert_eq!(
+            results
+                .iter()
+                .map(|item| item.name.as_str())
+                .collect::<Vec<_>>(),
+            vec![
+                "benches::arithmetic::bench_add",
+                "benches::arithmetic::bench_multiply"
+            ]
+        );
+
+        assert_eq!(
+            failures,
+            vec![BenchmarkFailure {
+                name: "benches::arithmetic::bench_failure".to_owned(),
+                message: "The function returned exit code 1.".to_owned()
+            }]
+        );
+
+        assert!(skips.is_empty());
+
+        // the baseline is saved even though a benchmark failed
+        let baseline = BenchmarkBaseline::load(&get_benchmark_baseline_file_path(&moudle_path_buf))
+            .unwrap()
+            .unwrap();
+        assert!(baseline
+            .items
+            .iter()
+            .any(|item| item.name == "benches::arithmetic::bench_multiply"));
+        assert!(!baseline
+            .items
+            .iter()
+            .any(|item| item.name == "benches::arithmetic::bench_failure"));
+
+        // the filter is applied to the benchmark functions
+        let (results, failures, skips) = launch_benchmarks(
+            &moudle_path_buf,
+            &UnitTestFilter {
+                patterns: vec!["benches::arithmetic::bench_mul".to_owned()],
+                ..UnitTestFilter::default()
+            },
+            vec![],
+            HashMap::<String, String>::new(),
+            &BenchmarkOptions {
+                warm_up_iterations: 0,
+                iterations: 1,
+                save_baseline: false,
+            },
+            &mut output,
+        )
+        .unwrap();
+
+        assert_eq!(
+            results
+                .iter()
+                .map(|item| item.name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["benches::arithmetic::bench_multiply"]
+        );
+        assert!(failures.is_empty());
+        assert_eq!(
+            skips,
+            vec![
+                "benches::arithmetic::bench_add",
+                "benches::arithmetic::bench_failure"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_benchmark_result() {
+        let result = BenchmarkResult::new(
+            "benches::bench_foo".to_owned(),
+            &[
+                Duration::from_millis(4),
+                Duration::from_millis(2),
+                Duration::from_millis(6),
+                Duration::from_millis(4),
+            ],
+            Some(Duration::from_millis(5)),
+        );
+
+        let is_close = |duration: Duration, secs: f64| (duration.as_secs_f64() - secs).abs() < 1e-6;
+
+        assert!(is_close(result.mean, 0.004));
+        assert!(is_close(result.median, 0.004));
+
+        // sqrt((0 + 4 + 4 + 0) / 4) = sqrt(2) milliseconds
+        assert!(is_close(result.deviation, 2f64.sqrt() / 1000.0));
+
+        assert!((result.change().unwrap() - (-0.2)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_runtime_limits() {
+        let runtime_config = RuntimeLimitConfig {
+            timeout: Some(10),
+            max_memory: Some(64),
+            max_threads: None,
+            max_instructions: Some(1000),
+        };
+
+        assert_eq!(
+            RuntimeLimits::default().merge_config(&runtime_config),
+            RuntimeLimits {
+                timeout: Some(Duration::from_secs(10)),
+                max_memory: Some(64 * 1024 * 1024),
+                max_threads: None,
+                max_instructions: Some(1000),
+            }
+        );
+
+        assert_eq!(
+            RuntimeLimits {
+                timeout: Some(Duration::from_secs(3)),
+                max_memory: None,
+                max_threads: Some(8),
+                max_instructions: None,
+            }
+            .merge_config(&runtime_config),
+            RuntimeLimits {
+                timeout: Some(Duration::from_secs(3)),
+                max_memory: Some(64 * 1024 * 1024),
+                max_threads: Some(8),
+                max_instructions: Some(1000),
+            }
+        );
+
+        assert_eq!(
+            RuntimeLimits::default().merge_config(&RuntimeLimitConfig::default()),
+            RuntimeLimits::default()
+        );
+    }
+
+    #[test]
+    fn test_application() {
+        let mut output: Vec<u8> = vec![];
+
+        let mut moudle_path_buf = get_resources_path_buf();
+        moudle_path_buf.push("single_module_with_multiple_executable_units");
+
+        let mut application =
+            Application::load(&moudle_path_buf, HashMap::new(), &mut output).unwrap();
+
+        assert!(application
+            .entry_point_names()
+            .iter()
+            .any(|name| name == "_start"));
+
+        // call repeatedly
+        for _ in 0..3 {
+            assert_eq!(application.call("_start", vec![]).unwrap(), 0);
+            assert_eq!(application.call("foo", vec![]).unwrap(), 0);
+            assert_eq!(application.call("bar", vec![]).unwrap(), 0);
+        }
+
+        assert!(application.call("baz", vec![]).is_err());
+    }
+
+    #[test]
+    fn test_embedded_application() {
+        let mut output: Vec<u8> = vec![];
+
+        let mut script_file_path_buf = get_resources_path_buf();
+        script_file_path_buf.push("single_file_app");
+        script_file_path_buf.push("no_conf.anca");
+
+        let runtime_property = RuntimeProperty::from_runtime_exec_file().unwrap();
+        let (common_entry, index_entry, image_data) =
+            build_application_by_single_file(&script_file_path_buf, &runtime_property, &mut output)
+                .unwrap();
+
+        // from image data
+        let result0 = EmbeddedApplicationBuilder::new(image_data).run("_start");
+        assert_eq!(result0.unwrap(), 0);
+
+        // from image entries
+        let result1 = EmbeddedApplicationBuilder::from_entries(&common_entry, &index_entry)
+            .unwrap()
+            .arguments(vec!["foo".to_owned()])
+            .run("_start");
+        assert_eq!(result1.unwrap(), 0);
     }
-}
 
-impl ProcessResource for ScriptFileProcessResource {
-    fn create_process_context(&self) -> Result<ProcessContext, ImageError> {
-        let mut module_images = vec![];
+    #[test]
+    fn test_unit_test_filter() {
+        let select = |filter: &UnitTestFilter| {
+            [
+                "foo::test_add",
+                "foo::test_add_overflow",
+                "foo::bar::test_get",
+                "foo::test_ignore_slow",
+            ]
+            .iter()
+            .filter(|name| filter.is_match(name))
+            .copied()
+            .collect::<Vec<_>>()
+        };
 
-        let main_module_image = ModuleImage::read(&self.main_image_data)?;
-        module_images.push(main_module_image);
+        assert_eq!(
+            select(&UnitTestFilter::default()),
+            vec![
+                "foo::test_add",
+                "foo::test_add_overflow",
+                "foo::bar::test_get"
+            ]
+        );
 
-        for file in &self.mapped_files {
-            let module_image = ModuleImage::read(file)?;
-            module_images.push(module_image);
-        }
+        // prefix
+        assert_eq!(
+            select(&UnitTestFilter {
+                patterns: vec!["foo::test_add".to_owned(), "foo::bar".to_owned()],
+                ..UnitTestFilter::default()
+            }),
+            vec![
+                "foo::test_add",
+                "foo::test_add_overflow",
+                "foo::bar::test_get"
+            ]
+        );
 
-        let process_context = ProcessContext::new(
-            &self.process_property,
-            &self.external_function_table,
-            module_images,
+        // exact
+        assert_eq!(
+            select(&UnitTestFilter {
+                patterns: vec!["foo::test_add".to_owned()],
+                exact: true,
+                ..UnitTestFilter::default()
+            }),
+            vec!["foo::test_add"]
         );
 
-        Ok(process_context)
+        // glob
+        assert_eq!(
+            select(&UnitTestFilter {
+                patterns: vec!["*::test_?et".to_owned()],
+                ..UnitTestFilter::default()
+            }),
+            vec!["foo::bar::test_get"]
+        );
+
+        // skip
+        assert_eq!(
+            select(&UnitTestFilter {
+                skip_patterns: vec!["*_overflow".to_owned(), "foo::bar".to_owned()],
+                ..UnitTestFilter::default()
+            }),
+            vec!["foo::test_add"]
+        );
+
+        // ignored
+        assert_eq!(
+            select(&UnitTestFilter {
+                ignored: true,
+                ..UnitTestFilter::default()
+            }),
+            vec!["foo::test_ignore_slow"]
+        );
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use std::{collections::HashMap, path::PathBuf};
+    #[test]
+    fn test_unit_test_expectation() {
+        assert_eq!(
+            UnitTestExpectation::from_unit_test_name("foo::test_add"),
+            UnitTestExpectation::Success
+        );
+        assert_eq!(
+            UnitTestExpectation::from_unit_test_name("foo::test_should_fail_overflow"),
+            UnitTestExpectation::Failure
+        );
+        assert_eq!(
+            UnitTestExpectation::from_unit_test_name("foo::test_exit_2_invalid_argument"),
+            UnitTestExpectation::ExitCode(2)
+        );
+        assert_eq!(
+            UnitTestExpectation::from_unit_test_name("foo::test_exit_code"),
+            UnitTestExpectation::Success
+        );
+        assert_eq!(
+            UnitTestExpectation::from_unit_test_name("foo::test_ignore_should_fail_slow"),
+            UnitTestExpectation::Failure
+        );
+        assert_eq!(
+            UnitTestExpectation::from_unit_test_name("foo::test_ignore_exit_3_slow"),
+            UnitTestExpectation::ExitCode(3)
+        );
 
-    use pretty_assertions::assert_eq;
+        let failure = UnitTestExpectation::Failure;
+        assert!(failure.is_satisfied_by(&UnitTestStatus::Exited(1)));
+        assert!(failure.is_satisfied_by(&UnitTestStatus::Error("trap".to_owned())));
+        assert!(!failure.is_satisfied_by(&UnitTestStatus::Exited(0)));
+        assert!(!failure.is_satisfied_by(&UnitTestStatus::TimedOut));
 
-    use crate::runner::{
-        launch_application, launch_single_file_application, launch_unit_tests, UnitTestResult,
-    };
+        let exit_code = UnitTestExpectation::ExitCode(2);
+        assert!(exit_code.is_satisfied_by(&UnitTestStatus::Exited(2)));
+        assert!(!exit_code.is_satisfied_by(&UnitTestStatus::Exited(0)));
+        assert!(!exit_code.is_satisfied_by(&UnitTestStatus::Error("trap".to_owned())));
+    }
 
-    fn get_resources_path_buf() -> PathBuf {
-        // `std::env::current_dir()` returns the current Rust project's root folder
-        let mut pwd = std::env::current_dir().unwrap();
-        pwd.push("tests");
-        pwd.push("resources");
-        pwd
+    #[test]
+    fn test_write_unit_test_report() {
+        let unit_test_results = vec![
+            UnitTestResult::new(
+                "foo::test_add".to_owned(),
+                UnitTestStatus::Exited(0),
+                Duration::from_millis(1),
+                String::new(),
+            ),
+            UnitTestResult::new(
+                "bar::test_divide".to_owned(),
+                UnitTestStatus::Error("Division by \"zero\".".to_owned()),
+                Duration::ZERO,
+                "Traceback:\n  divide\n".to_owned(),
+            ),
+        ];
+        let filter_out_names = vec!["bar::test_multiply".to_owned()];
+
+        // json
+        {
+            let mut output: Vec<u8> = vec![];
+            write_unit_test_report(
+                UnitTestReportFormat::Json,
+                &unit_test_results,
+                &filter_out_names,
+                &mut output,
+            )
+            .unwrap();
+
+            assert_eq!(
+                String::from_utf8(output).unwrap(),
+                r#"{
+    "passed": 1,
+    "failed": 1,
+    "skipped": 1,
+    "tests": [
+        {
+            "name": "foo::test_add",
+            "submodule": "foo",
+            "status": "passed",
+            "exit_code": 0,
+            "duration": 0.001,
+            "message": null,
+            "output": ""
+        },
+        {
+            "name": "bar::test_divide",
+            "submodule": "bar",
+            "status": "error",
+            "exit_code": null,
+            "duration": 0.0,
+            "message": "Division by \"zero\".",
+            "output": "Traceback:\n  divide\n"
+        },
+        {
+            "name": "bar::test_multiply",
+            "submodule": "bar",
+            "status": "skipped",
+            "exit_code": null,
+            "duration": 0.0,
+            "message": null,
+            "output": ""
+        }
+    ]
+}
+"#
+            );
+        }
+
+        // junit
+        {
+            let mut output: Vec<u8> = vec![];
+            write_unit_test_report(
+                UnitTestReportFormat::JUnit,
+                &unit_test_results,
+                &filter_out_names,
+                &mut output,
+            )
+            .unwrap();
+
+            assert_eq!(
+                String::from_utf8(output).unwrap(),
+                r#"<?xml version="1.0" encoding="UTF-8"?>
+<testsuites tests="3" failures="0" errors="1" skipped="1" time="0.001">
+    <testsuite name="foo" tests="1" failures="0" errors="0" skipped="0" time="0.001">
+        <testcase name="test_add" classname="foo" time="0.001"/>
+    </testsuite>
+    <testsuite name="bar" tests="2" failures="0" errors="1" skipped="1" time="0.000">
+        <testcase name="test_divide" classname="bar" time="0.000">
+            <error message="Division by &quot;zero&quot;."/>
+            <system-out>Traceback:&#10;  divide&#10;</system-out>
+        </testcase>
+        <testcase name="test_multiply" classname="bar" time="0.000">
+            <skipped/>
+        </testcase>
+    </testsuite>
+</testsuites>
+"#
+            );
+        }
     }
 
     #[test]
-    fn test_launch_application() {
+    fn test_launch_script_application() {
         let mut output: Vec<u8> = vec![];
         // let mut output = stdout();
 
-        // single_module_app
+        // no config
         {
-            let mut moudle_path_buf = get_resources_path_buf();
-            moudle_path_buf.push("single_module_app");
+            let mut script_file_path_buf = get_resources_path_buf();
+            script_file_path_buf.push("single_file_app");
+            script_file_path_buf.push("no_conf.anca");
 
-            let result0 = launch_application(
-                &moudle_path_buf,
-                "",
+            let result0 = launch_single_file_application(
+                &script_file_path_buf,
                 vec![],
                 HashMap::<String, String>::new(),
                 &mut output,
@@ -422,159 +3961,344 @@ mod tests {
             assert_eq!(result0.unwrap(), 0);
         }
 
-        // single_module_with_multiple_executable_units
+        // with config
         {
-            let mut moudle_path_buf = get_resources_path_buf();
-            moudle_path_buf.push("single_module_with_multiple_executable_units");
+            let mut script_file_path_buf = get_resources_path_buf();
+            script_file_path_buf.push("single_file_app");
+            script_file_path_buf.push("with_conf.anca");
 
-            let result0 = launch_application(
-                &moudle_path_buf,
-                "",
+            let result0 = launch_single_file_application(
+                &script_file_path_buf,
                 vec![],
                 HashMap::<String, String>::new(),
                 &mut output,
             );
 
             assert_eq!(result0.unwrap(), 0);
+        }
 
-            let result1 = launch_application(
-                &moudle_path_buf,
+        // with executable units
+        {
+            let mut script_file_path_buf = get_resources_path_buf();
+            script_file_path_buf.push("single_file_app");
+            script_file_path_buf.push("with_units.anca");
+
+            let result0 = launch_single_file_application(
+                &script_file_path_buf,
+                vec![],
+                HashMap::<String, String>::new(),
+                &mut output,
+            );
+
+            assert_eq!(result0.unwrap(), 0);
+
+            let result1 = launch_single_file_application_with_limits(
+                &script_file_path_buf,
                 ":foo",
                 vec![],
                 HashMap::<String, String>::new(),
+                &RuntimeLimits::default(),
+                false,
                 &mut output,
             );
 
-            assert_eq!(result1.unwrap(), 0);
+            assert_eq!(result1.unwrap(), 11);
 
-            let result2 = launch_application(
-                &moudle_path_buf,
+            let result2 = launch_single_file_application_with_limits(
+                &script_file_path_buf,
                 ":bar",
                 vec![],
                 HashMap::<String, String>::new(),
+                &RuntimeLimits::default(),
+                true,
                 &mut output,
             );
 
-            assert_eq!(result2.unwrap(), 0);
-        }
-
-        // multiple_modules
-        {
-            let mut moudle_path_buf = get_resources_path_buf();
-            moudle_path_buf.push("multiple_modules");
-            moudle_path_buf.push("app");
+            assert_eq!(result2.unwrap(), 13);
 
-            let result0 = launch_application(
-                &moudle_path_buf,
-                "",
+            let result3 = launch_single_file_application_with_limits(
+                &script_file_path_buf,
+                ":baz",
                 vec![],
                 HashMap::<String, String>::new(),
+                &RuntimeLimits::default(),
+                true,
                 &mut output,
             );
 
-            assert_eq!(result0.unwrap(), 0);
+            assert!(result3.is_err());
+
+            assert_eq!(
+                list_executable_units(&script_file_path_buf, &mut output).unwrap()[0],
+                "_start"
+            );
         }
     }
 
     #[test]
-    fn test_launch_unit_tests() {
-        let mut output: Vec<u8> = vec![];
-        // let mut output = stdout();
+    fn test_launch_script_application_with_limits() {
+        let mut script_file_path_buf = get_resources_path_buf();
+        script_file_path_buf.push("single_file_app");
+        script_file_path_buf.push("loop_forever.anca");
 
-        // single_module_with_unit_tests - without specify testing name
+        // time limit
         {
-            let mut moudle_path_buf = get_resources_path_buf();
-            moudle_path_buf.push("single_module_with_unit_tests");
+            let mut output: Vec<u8> = vec![];
+            let start_instant = Instant::now();
 
-            let (results, skips) = launch_unit_tests(
-                &moudle_path_buf,
+            let result0 = launch_single_file_application_with_limits(
+                &script_file_path_buf,
                 "",
                 vec![],
                 HashMap::<String, String>::new(),
+                &RuntimeLimits {
+                    timeout: Some(Duration::from_millis(300)),
+                    ..RuntimeLimits::default()
+                },
+                false,
                 &mut output,
-            )
-            .unwrap();
-
-            assert_eq!(
-                results,
-                vec![
-                    UnitTestResult::new("foo::test_add".to_owned(), true),
-                    UnitTestResult::new("foo::test_subtract".to_owned(), true),
-                    UnitTestResult::new("bar::test_multiply".to_owned(), true),
-                    UnitTestResult::new("bar::test_divide".to_owned(), true),
-                ]
             );
 
-            assert!(skips.is_empty());
+            assert_eq!(result0.unwrap(), EXIT_CODE_TIME_LIMIT_EXCEEDED);
+            assert!(start_instant.elapsed() < Duration::from_secs(10));
+
+            let text = String::from_utf8(output).unwrap();
+            assert!(text.contains("The application exceeded the time limit of 0.300s."));
         }
 
-        // single_module_with_unit_tests - specify testing name
+        // memory limit, the application allocates memory until `malloc()` fails.
         {
-            let mut moudle_path_buf = get_resources_path_buf();
-            moudle_path_buf.push("single_module_with_unit_tests");
+            let mut script_file_path_buf = get_resources_path_buf();
+            script_file_path_buf.push("single_file_app");
+            script_file_path_buf.push("grow_memory.anca");
 
-            let (results, skips) = launch_unit_tests(
-                &moudle_path_buf,
-                "foo",
+            let mut output: Vec<u8> = vec![];
+
+            let result0 = launch_single_file_application_with_limits(
+                &script_file_path_buf,
+                "",
                 vec![],
                 HashMap::<String, String>::new(),
+                &RuntimeLimits {
+                    timeout: Some(Duration::from_secs(10)),
+                    max_memory: Some(256 * 1024 * 1024),
+                    ..RuntimeLimits::default()
+                },
+                false,
                 &mut output,
-            )
-            .unwrap();
-
-            assert_eq!(
-                results,
-                vec![
-                    UnitTestResult::new("foo::test_add".to_owned(), true),
-                    UnitTestResult::new("foo::test_subtract".to_owned(), true),
-                ]
             );
 
-            assert_eq!(
-                skips,
-                vec![
-                    "bar::test_multiply".to_owned(),
-                    "bar::test_divide".to_owned()
-                ]
-            );
+            assert_eq!(result0.unwrap(), 1);
         }
-    }
-
-    #[test]
-    fn test_launch_script_application() {
-        let mut output: Vec<u8> = vec![];
-        // let mut output = stdout();
 
-        // no config
+        // instructions limit
         {
-            let mut script_file_path_buf = get_resources_path_buf();
-            script_file_path_buf.push("single_file_app");
-            script_file_path_buf.push("no_conf.anca");
+            let mut output: Vec<u8> = vec![];
 
-            let result0 = launch_single_file_application(
+            let result0 = launch_single_file_application_with_limits(
                 &script_file_path_buf,
+                "",
                 vec![],
                 HashMap::<String, String>::new(),
+                &RuntimeLimits {
+                    max_instructions: Some(1000),
+                    ..RuntimeLimits::default()
+                },
+                false,
                 &mut output,
             );
 
-            assert_eq!(result0.unwrap(), 0);
+            assert!(result0.is_err());
         }
 
-        // with config
+        // the limits that are not exceeded
         {
             let mut script_file_path_buf = get_resources_path_buf();
             script_file_path_buf.push("single_file_app");
-            script_file_path_buf.push("with_conf.anca");
+            script_file_path_buf.push("with_units.anca");
 
-            let result0 = launch_single_file_application(
+            let mut output: Vec<u8> = vec![];
+
+            let result0 = launch_single_file_application_with_limits(
                 &script_file_path_buf,
+                ":foo",
                 vec![],
                 HashMap::<String, String>::new(),
+                &RuntimeLimits {
+                    timeout: Some(Duration::from_secs(10)),
+                    max_memory: Some(1024 * 1024 * 1024),
+                    max_threads: Some(256),
+                    max_instructions: None,
+                },
+                false,
                 &mut output,
             );
 
-            assert_eq!(result0.unwrap(), 0);
+            assert_eq!(result0.unwrap(), 11);
+        }
+    }
+
+    #[test]
+    fn test_list_executable_units() {
+        let mut output: Vec<u8> = vec![];
+
+        // module
+        {
+            let mut moudle_path_buf = get_resources_path_buf();
+            moudle_path_buf.push("single_module_with_multiple_executable_units");
+
+            let mut entry_point_names =
+                list_executable_units(&moudle_path_buf, &mut output).unwrap();
+            entry_point_names[1..].sort();
+            assert_eq!(entry_point_names, vec!["_start", "bar", "foo"]);
+        }
+
+        // single-file application
+        {
+            let mut script_file_path_buf = get_resources_path_buf();
+            script_file_path_buf.push("single_file_app");
+            script_file_path_buf.push("with_units.anca");
+
+            let mut entry_point_names =
+                list_executable_units(&script_file_path_buf, &mut output).unwrap();
+            entry_point_names[1..].sort();
+            assert_eq!(entry_point_names, vec!["_start", "bar", "foo"]);
+        }
+    }
+
+    #[test]
+    fn test_list_entry_points() {
+        let mut output: Vec<u8> = vec![];
+
+        // module with executable units
+        {
+            let mut moudle_path_buf = get_resources_path_buf();
+            moudle_path_buf.push("single_module_with_multiple_executable_units");
+
+            assert_eq!(
+                list_entry_points(&moudle_path_buf, true, &mut output).unwrap(),
+                ApplicationEntryPoints {
+                    default_entry_point: Some("_start".to_owned()),
+                    executable_units: vec!["bar".to_owned(), "foo".to_owned()],
+                    unit_tests: vec![],
+                }
+            );
+        }
+
+        // module with unit tests
+        {
+            let mut moudle_path_buf = get_resources_path_buf();
+            moudle_path_buf.push("single_module_with_unit_tests");
+
+            assert_eq!(
+                list_entry_points(&moudle_path_buf, true, &mut output).unwrap(),
+                ApplicationEntryPoints {
+                    default_entry_point: None,
+                    executable_units: vec![],
+                    unit_tests: vec![
+                        "bar::test_divide".to_owned(),
+                        "bar::test_multiply".to_owned(),
+                        "foo::test_add".to_owned(),
+                        "foo::test_subtract".to_owned(),
+                    ],
+                }
+            );
         }
     }
+
+    #[test]
+    fn test_write_entry_points() {
+        let entry_points = ApplicationEntryPoints {
+            default_entry_point: Some("_start".to_owned()),
+            executable_units: vec!["bar".to_owned(), "foo".to_owned()],
+            unit_tests: vec!["foo::test_add".to_owned()],
+        };
+
+        let mut text: Vec<u8> = vec![];
+        write_entry_points(EntryPointListFormat::Text, &entry_points, &mut text).unwrap();
+        assert_eq!(
+            String::from_utf8(text).unwrap(),
+            "_start (default)\n:bar\n:foo\nfoo::test_add (test)\n"
+        );
+
+        let mut json: Vec<u8> = vec![];
+        write_entry_points(EntryPointListFormat::Json, &entry_points, &mut json).unwrap();
+        assert_eq!(
+            String::from_utf8(json).unwrap(),
+            r#"{
+    "default": "_start",
+    "units": [
+        ":bar",
+        ":foo"
+    ],
+    "tests": [
+        "foo::test_add"
+    ]
+}
+"#
+        );
+
+        let mut empty_json: Vec<u8> = vec![];
+        write_entry_points(
+            EntryPointListFormat::Json,
+            &ApplicationEntryPoints::default(),
+            &mut empty_json,
+        )
+        .unwrap();
+        assert_eq!(
+            String::from_utf8(empty_json).unwrap(),
+            r#"{
+    "default": null,
+    "units": [],
+    "tests": []
+}
+"#
+        );
+    }
+
+    #[test]
+    fn test_repl() {
+        let mut output: Vec<u8> = vec![];
+
+        let mut repl = Repl::new(&get_resources_path_buf()).unwrap();
+
+        // expression
+        assert_eq!(
+            repl.eval("imm_i32(7)", &mut output).unwrap(),
+            ReplOutput::Value(7)
+        );
+
+        // definition
+        assert_eq!(
+            repl.eval(
+                r#"
+pub fn add(left:i32, right:i32) -> i32 {
+    add_i32(
+        local_load_i32_s(left)
+        local_load_i32_s(right)
+    )
+}"#,
+                &mut output
+            )
+            .unwrap(),
+            ReplOutput::Defined
+        );
+
+        // call the previous definition
+        assert_eq!(
+            repl.eval("call(add, imm_i32(11), imm_i32(13))", &mut output)
+                .unwrap(),
+            ReplOutput::Value(24)
+        );
+
+        // the incorrect definition is not kept
+        assert!(repl.eval("fn broken(", &mut output).is_err());
+        assert_eq!(repl.definitions().len(), 1);
+
+        // the definition is removed
+        repl.reset();
+        assert!(repl
+            .eval("call(add, imm_i32(11), imm_i32(13))", &mut output)
+            .is_err());
+    }
 }